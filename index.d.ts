@@ -41,7 +41,6 @@ export interface ChannelDetails {
   unspendablePunishmentReserve?: number
   userChannelId: UserChannelId
   feerateSatPer1000Weight: number
-  balanceMsat: number
   outboundCapacityMsat: number
   inboundCapacityMsat: number
   confirmationsRequired?: number
@@ -52,19 +51,47 @@ export interface ChannelDetails {
   isPublic: boolean
   cltvExpiryDelta?: number
 }
+export const enum NodeLifecycleState {
+  Built = 'Built',
+  Running = 'Running',
+  Stopped = 'Stopped',
+}
+export const enum LifecycleError {
+  AlreadyRunning = 'AlreadyRunning',
+  NotRunning = 'NotRunning',
+}
 export interface PaymentPreimage {
   field0: Array<number>
 }
 export interface PaymentSecret {
   field0: Array<number>
 }
+export interface PaymentId {
+  field0: Array<number>
+}
+export const enum PaymentKind {
+  Onchain = 'Onchain',
+  Bolt11 = 'Bolt11',
+  Bolt11Jit = 'Bolt11Jit',
+  Bolt12Offer = 'Bolt12Offer',
+  Bolt12Refund = 'Bolt12Refund',
+  Spontaneous = 'Spontaneous',
+}
 export interface PaymentDetails {
-  hash: PaymentHash
+  id: PaymentId
+  kind: PaymentKind
+  hash?: PaymentHash
   preimage?: PaymentPreimage
   secret?: PaymentSecret
-  amountMsat?: number
+  amountMsat?: bigint
   direction: PaymentDirection
   status: PaymentStatus
+  lspFeeLimits?: LspFeeLimits
+  offerId?: OfferId
+}
+export interface LspFeeLimits {
+  maxTotalOpeningFeeMsat?: bigint
+  maxProportionalOpeningFeePpmMsat?: bigint
 }
 export const enum PaymentStatus {
   Pending = 'Pending',
@@ -84,9 +111,129 @@ export interface Address {
 export interface Txid {
   feild0: string
 }
+export interface BalanceDetails {
+  totalOnchainBalanceSats: bigint
+  spendableOnchainBalanceSats: bigint
+  totalLightningBalanceSats: bigint
+  lightningBalances: Array<LightningBalance>
+  pendingBalancesFromChannelClosures: Array<PendingSweepBalance>
+}
+export const enum LightningBalanceKind {
+  ClaimableOnChannelClose = 'ClaimableOnChannelClose',
+  ClaimableAwaitingConfirmations = 'ClaimableAwaitingConfirmations',
+  ContentiousClaimable = 'ContentiousClaimable',
+  MaybeTimeoutClaimableHTLC = 'MaybeTimeoutClaimableHTLC',
+  MaybePreimageClaimableHTLC = 'MaybePreimageClaimableHTLC',
+  CounterpartyRevokedOutputClaimable = 'CounterpartyRevokedOutputClaimable',
+}
+export interface LightningBalance {
+  kind: LightningBalanceKind
+  channelId: ChannelId
+  counterpartyNodeId: string
+  amountSatoshis: bigint
+  confirmationHeight?: number
+  timeoutHeight?: number
+  claimableHeight?: number
+  expiryHeight?: number
+  paymentHash?: PaymentHash
+  paymentPreimage?: PaymentPreimage
+}
+export const enum PendingSweepBalanceKind {
+  PendingBroadcast = 'PendingBroadcast',
+  BroadcastAwaitingConfirmation = 'BroadcastAwaitingConfirmation',
+  AwaitingThresholdConfirmations = 'AwaitingThresholdConfirmations',
+}
+export interface PendingSweepBalance {
+  kind: PendingSweepBalanceKind
+  channelId?: ChannelId
+  amountSatoshis: bigint
+  latestBroadcastHeight?: number
+  latestSpendingTxid?: Txid
+  confirmationHash?: string
+  confirmationHeight?: number
+}
+export interface RoutingFees {
+  baseMsat: number
+  proportionalMillionths: number
+}
+export interface ChannelUpdateInfo {
+  lastUpdate: number
+  enabled: boolean
+  cltvExpiryDelta: number
+  htlcMinimumMsat: bigint
+  htlcMaximumMsat: bigint
+  fees: RoutingFees
+}
+export interface ChannelInfo {
+  shortChannelId: bigint
+  nodeOne: string
+  oneToTwo?: ChannelUpdateInfo
+  nodeTwo: string
+  twoToOne?: ChannelUpdateInfo
+  capacitySats?: bigint
+}
+export interface NodeAnnouncementInfo {
+  lastUpdate: number
+  alias: string
+  color: string
+  addresses: Array<string>
+  features: string
+}
+export interface NodeInfo {
+  nodeId: string
+  channels: Array<bigint>
+  announcementInfo?: NodeAnnouncementInfo
+}
+export interface BestBlock {
+  blockHash: string
+  height: number
+}
+export interface NodeStatus {
+  isRunning: boolean
+  isListening: boolean
+  currentBestBlock: BestBlock
+  latestWalletSyncTimestamp?: number
+  latestOnchainWalletSyncTimestamp?: number
+  latestFeeRateCacheUpdateTimestamp?: number
+  latestRgsSnapshotTimestamp?: number
+  latestNodeAnnouncementBroadcastTimestamp?: number
+  connectedPeers: number
+  usableChannels: number
+}
+export interface OfferId {
+  field0: Array<number>
+}
+export interface Offer {
+  offer: string
+  id: OfferId
+  description: string
+  amountMsat?: bigint
+  issuer?: string
+  absoluteExpirySecs?: bigint
+}
+export interface Refund {
+  refund: string
+  description: string
+  amountMsat: bigint
+  payerNote?: string
+  absoluteExpirySecs?: bigint
+}
+export interface UnifiedPaymentResult {
+  paymentId?: PaymentId
+  txid?: Txid
+}
+export const enum GraphExportFormat {
+  Json = 'json',
+  Dot = 'dot',
+}
+export interface ExportNetworkGraphOptions {
+  format: 'json' | 'dot'
+  path: string
+}
 export interface PaymentSuccessful {
   paymentHash: PaymentHash
 }
+export function decodeLnurl(lnurl: string): string
 export class ChannelConfig {
   forwardingFeeProportionalMillionths: number
   forwardingFeeBaseMsat: number
@@ -120,7 +267,6 @@ export class Config {
     logDirPath: string | undefined | null,
     network: Network,
     listeningAddress: NetAddress,
-    defaultCltvExpiryDelta: number,
     onchainWalletSyncIntervalSecs: number,
     walletSyncIntervalSecs: number,
     feeRateCacheUpdateIntervalSecs: number,
@@ -140,12 +286,22 @@ export class Builder {
   setStorageDirPath(storageDirPath: string): boolean
   setNetwork(network: Network): boolean
   setListeningAddress(listeningAddress: NetAddress): boolean
+  setLiquiditySourceLsps2(nodeId: PublicKey, address: NetAddress, token?: string | undefined | null): boolean
   setLogLevel(level: LogLevel): boolean
+  setMetricsPort(port: number): boolean
   build(): Node
 }
+export class NetworkGraph {
+  listChannels(): Array<bigint>
+  channel(shortChannelId: bigint): ChannelInfo | null
+  listNodes(): Array<string>
+  node(nodeId: PublicKey): NodeInfo | null
+}
 export class Node {
   start(): boolean
   stop(): boolean
+  lifecycleState(): NodeLifecycleState
+  status(): NodeStatus
   syncWallets(): boolean
   nodeId(): string
   listeningAddress(): string | null
@@ -154,6 +310,7 @@ export class Node {
   sendAllToOnchainAddress(address: Address): Txid
   spendableOnchainBalanceSats(): bigint
   totalOnchainBalanceSats(): bigint
+  listBalances(): BalanceDetails
   connect(nodeId: PublicKey, address: NetAddress, persist: boolean): boolean
   disconnect(counterpartyNodeId: PublicKey): boolean
   connectOpenChannel(
@@ -165,16 +322,50 @@ export class Node {
     announceChannel: boolean,
   ): boolean
   closeChannel(channelId: ChannelId, counterpartyNodeId: PublicKey): boolean
+  forceCloseChannel(channelId: ChannelId, counterpartyNodeId: PublicKey): boolean
+  forceCloseAllChannels(): boolean
   receivePayment(amountMsat: number, description: string, expirySecs: number): string
   receiveVariableAmountPayment(description: string, expirySecs: number): string
-  sendPayment(invoice: string): PaymentHash
-  sendPaymentUsingAmount(invoice: string, amountMsat: number): PaymentHash
-  sendSpontaneousPayment(amountMsat: number, nodeId: PublicKey): PaymentHash
+  receivePaymentViaJitChannel(
+    amountMsat: number,
+    description: string,
+    expirySecs: number,
+    maxTotalLspFeeLimitMsat?: number | undefined | null,
+  ): string
+  receiveVariableAmountPaymentViaJitChannel(
+    description: string,
+    expirySecs: number,
+    maxProportionalLspFeeLimitPpmMsat?: number | undefined | null,
+  ): string
+  receiveUnified(amountSats: number, description: string, expirySecs: number): string
+  payUnified(uri: string): UnifiedPaymentResult
+  sendPayment(invoice: string): PaymentId
+  sendPaymentUsingAmount(invoice: string, amountMsat: number): PaymentId
+  receiveOffer(amountMsat: number, description: string): Offer
+  receiveVariableAmountOffer(description: string): Offer
+  sendOfferPayment(offer: string, payerNote?: string | undefined | null): PaymentId
+  sendOfferPaymentUsingAmount(offer: string, amountMsat: number, payerNote?: string | undefined | null): PaymentId
+  initiateRefund(amountMsat: number, expirySecs: number): Refund
+  requestRefundPayment(refund: string): PaymentHash
+  sendSpontaneousPayment(amountMsat: number, nodeId: PublicKey): PaymentId
+  sendPaymentProbes(invoice: string): boolean
+  sendSpontaneousPaymentProbes(amountMsat: number, nodeId: PublicKey): boolean
+  lnurlPay(lnurl: string, amountMsat: number, comment?: string | undefined | null): Promise<PaymentId>
+  payLightningAddress(address: string, amountMsat: number, comment?: string | undefined | null): Promise<PaymentId>
+  lnurlWithdraw(
+    lnurl: string,
+    amountMsat: number | undefined | null,
+    description: string | undefined | null,
+    expirySecs: number,
+  ): Promise<PaymentId>
+  lnurlAuth(lnurl: string): Promise<string>
   listPayments(): Array<PaymentDetails>
   listPeers(): Array<PeerDetails>
   listChannels(): Array<ChannelDetails>
-  payment(paymentHash: PaymentHash): PaymentDetails
-  removePayment(paymentHash: PaymentHash): boolean
+  networkGraph(): NetworkGraph
+  exportNetworkGraph(options: ExportNetworkGraphOptions): Promise<boolean>
+  payment(paymentId: PaymentId): PaymentDetails | null
+  removePayment(paymentId: PaymentId): boolean
   signMessage(msg: Array<number>): string
   verifySignature(msg: Array<number>, sig: string, pkey: PublicKey): boolean
   updateChannelConfig(channelId: ChannelId, counterpartyNodeId: PublicKey, channelConfig: ChannelConfig): boolean
@@ -237,8 +237,15 @@ if (!nativeBinding) {
 const {
   Network,
   LogLevel,
+  NodeLifecycleState,
+  LifecycleError,
+  PaymentKind,
   PaymentStatus,
   PaymentDirection,
+  LightningBalanceKind,
+  PendingSweepBalanceKind,
+  GraphExportFormat,
+  decodeLnurl,
   ChannelConfig,
   ChannelPending,
   PaymentFailed,
@@ -249,13 +256,21 @@ const {
   PublicKey,
   Config,
   Builder,
+  NetworkGraph,
   Node,
 } = nativeBinding
 
 module.exports.Network = Network
 module.exports.LogLevel = LogLevel
+module.exports.NodeLifecycleState = NodeLifecycleState
+module.exports.LifecycleError = LifecycleError
+module.exports.PaymentKind = PaymentKind
 module.exports.PaymentStatus = PaymentStatus
 module.exports.PaymentDirection = PaymentDirection
+module.exports.LightningBalanceKind = LightningBalanceKind
+module.exports.PendingSweepBalanceKind = PendingSweepBalanceKind
+module.exports.GraphExportFormat = GraphExportFormat
+module.exports.decodeLnurl = decodeLnurl
 module.exports.ChannelConfig = ChannelConfig
 module.exports.ChannelPending = ChannelPending
 module.exports.PaymentFailed = PaymentFailed
@@ -266,4 +281,5 @@ module.exports.NetAddress = NetAddress
 module.exports.PublicKey = PublicKey
 module.exports.Config = Config
 module.exports.Builder = Builder
+module.exports.NetworkGraph = NetworkGraph
 module.exports.Node = Node
@@ -0,0 +1,276 @@
+use std::str::FromStr;
+
+use ldk_node::bitcoin::amount::Denomination;
+use ldk_node::bitcoin::Address;
+use ldk_node::bitcoin::Amount;
+use ldk_node::bitcoin::Network;
+use ldk_node::lightning::offers::offer::Offer;
+use ldk_node::lightning_invoice::Bolt11Invoice;
+use ldk_node::NodeError;
+use napi_derive::napi;
+use url::form_urlencoded;
+
+use crate::utils::node_error;
+use crate::utils::Error;
+use crate::utils::PaymentId;
+use crate::utils::Txid;
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct UnifiedPaymentResult {
+  pub payment_id: Option<PaymentId>,
+  pub txid: Option<Txid>,
+}
+
+pub struct UnifiedUri {
+  pub address: Option<Address>,
+  pub amount_sats: Option<u64>,
+  pub invoice: Option<Bolt11Invoice>,
+  pub offer: Option<Offer>,
+}
+
+pub fn build(
+  address: &Address,
+  amount_sats: u64,
+  description: &str,
+  invoice: &Bolt11Invoice,
+  offer: Option<&Offer>,
+) -> String {
+  let amount = Amount::from_sat(amount_sats).to_string_in(Denomination::Bitcoin);
+  let mut query = form_urlencoded::Serializer::new(String::new());
+  query
+    .append_pair("amount", &amount)
+    .append_pair("message", description)
+    .append_pair("lightning", &invoice.to_string());
+  if let Some(offer) = offer {
+    query.append_pair("lno", &offer.to_string());
+  }
+  format!("bitcoin:{}?{}", address, query.finish())
+}
+
+pub fn parse(uri: &str, network: Network) -> Result<UnifiedUri, Error> {
+  let uri = uri.trim();
+  let rest = match uri.get(..8) {
+    Some(scheme) if scheme.eq_ignore_ascii_case("bitcoin:") => &uri[8..],
+    _ => return Err(node_error(format!("Not a BIP21 URI: {}", uri))),
+  };
+  let (address, query) = match rest.split_once('?') {
+    Some((address, query)) => (address, query),
+    None => (rest, ""),
+  };
+
+  let mut parsed = UnifiedUri {
+    address: None,
+    amount_sats: None,
+    invoice: None,
+    offer: None,
+  };
+  if !address.is_empty() {
+    let address = Address::from_str(address).map_err(|e| node_error(e.to_string()))?;
+    parsed.address = Some(
+      address
+        .require_network(network)
+        .map_err(|e| node_error(e.to_string()))?,
+    );
+  }
+  for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+    match key.to_lowercase().as_str() {
+      "amount" => {
+        let amount = Amount::from_str_in(&value, Denomination::Bitcoin)
+          .map_err(|e| node_error(e.to_string()))?;
+        parsed.amount_sats = Some(amount.to_sat());
+      }
+      "lightning" => {
+        parsed.invoice =
+          Some(Bolt11Invoice::from_str(&value).map_err(|e| node_error(e.to_string()))?);
+      }
+      "lno" => {
+        parsed.offer =
+          Some(Offer::from_str(&value).map_err(|e| node_error(format!("Invalid offer: {:?}", e)))?);
+      }
+      key if key.starts_with("req-") => {
+        return Err(node_error(format!(
+          "Unsupported required BIP21 parameter: {}",
+          key
+        )));
+      }
+      _ => {}
+    }
+  }
+  Ok(parsed)
+}
+
+/// Whether a failed Lightning attempt is known not to have sent any HTLC, so
+/// that paying the same URI on-chain cannot pay twice.
+fn nothing_sent(e: &NodeError) -> bool {
+  matches!(
+    e,
+    NodeError::PaymentSendingFailed | NodeError::InvalidInvoice | NodeError::InvalidAmount
+  )
+}
+
+fn pay_offer(
+  node: &ldk_node::Node,
+  offer: &Offer,
+  amount_sats: Option<u64>,
+) -> Result<ldk_node::lightning::ln::channelmanager::PaymentId, NodeError> {
+  match (offer.amount(), amount_sats) {
+    (None, Some(amount_sats)) => {
+      node
+        .bolt12_payment()
+        .send_using_amount(offer, None, amount_sats * 1000)
+    }
+    _ => node.bolt12_payment().send(offer, None),
+  }
+}
+
+fn pay_invoice(
+  node: &ldk_node::Node,
+  invoice: &Bolt11Invoice,
+  amount_sats: Option<u64>,
+) -> Result<ldk_node::lightning::ln::channelmanager::PaymentId, NodeError> {
+  match (invoice.amount_milli_satoshis(), amount_sats) {
+    (None, Some(amount_sats)) => node
+      .bolt11_payment()
+      .send_using_amount(invoice, amount_sats * 1000),
+    _ => node.bolt11_payment().send(invoice),
+  }
+}
+
+fn paid(payment_id: ldk_node::lightning::ln::channelmanager::PaymentId) -> UnifiedPaymentResult {
+  UnifiedPaymentResult {
+    payment_id: Some(PaymentId::from_ldk_node(payment_id)),
+    txid: None,
+  }
+}
+
+pub fn pay(node: &ldk_node::Node, uri: &UnifiedUri) -> Result<UnifiedPaymentResult, Error> {
+  let mut lightning_error = None;
+  if let Some(offer) = &uri.offer {
+    match pay_offer(node, offer, uri.amount_sats) {
+      Ok(payment_id) => return Ok(paid(payment_id)),
+      Err(e) if nothing_sent(&e) => lightning_error = Some(e.to_string()),
+      Err(e) => return Err(node_error(e.to_string())),
+    }
+  }
+  if let Some(invoice) = &uri.invoice {
+    match pay_invoice(node, invoice, uri.amount_sats) {
+      Ok(payment_id) => return Ok(paid(payment_id)),
+      Err(e) if nothing_sent(&e) => lightning_error = Some(e.to_string()),
+      Err(e) => return Err(node_error(e.to_string())),
+    }
+  }
+
+  match (&uri.address, uri.amount_sats) {
+    (Some(address), Some(amount_sats)) => {
+      match node.onchain_payment().send_to_address(address, amount_sats) {
+        Ok(txid) => Ok(UnifiedPaymentResult {
+          payment_id: None,
+          txid: Some(Txid::from_ldk_node(txid)),
+        }),
+        Err(e) => Err(node_error(e.to_string())),
+      }
+    }
+    _ => Err(node_error(lightning_error.unwrap_or_else(|| {
+      "BIP21 URI has neither a Lightning invoice nor an on-chain amount".to_string()
+    }))),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use ldk_node::bitcoin::hashes::sha256;
+  use ldk_node::bitcoin::hashes::Hash;
+  use ldk_node::bitcoin::secp256k1::Secp256k1;
+  use ldk_node::bitcoin::secp256k1::SecretKey;
+  use ldk_node::lightning::ln::PaymentSecret;
+  use ldk_node::lightning_invoice::Currency;
+  use ldk_node::lightning_invoice::InvoiceBuilder;
+
+  use super::*;
+
+  const MAINNET_ADDRESS: &str = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+  const TESTNET_ADDRESS: &str = "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx";
+
+  fn invoice(amount_msat: u64) -> Bolt11Invoice {
+    let secp = Secp256k1::new();
+    let key = SecretKey::from_slice(&[42; 32]).unwrap();
+    InvoiceBuilder::new(Currency::Bitcoin)
+      .description("coffee".to_string())
+      .payment_hash(sha256::Hash::hash(b"preimage"))
+      .payment_secret(PaymentSecret([7; 32]))
+      .duration_since_epoch(Duration::from_secs(1_700_000_000))
+      .min_final_cltv_expiry_delta(144)
+      .amount_milli_satoshis(amount_msat)
+      .build_signed(|hash| secp.sign_ecdsa_recoverable(hash, &key))
+      .unwrap()
+  }
+
+  fn mainnet_address() -> Address {
+    Address::from_str(MAINNET_ADDRESS)
+      .unwrap()
+      .require_network(Network::Bitcoin)
+      .unwrap()
+  }
+
+  #[test]
+  fn build_round_trips_through_parse() {
+    let invoice = invoice(50_000_000);
+    let uri = build(&mainnet_address(), 50_000, "coffee & cake", &invoice, None);
+    assert!(uri.starts_with(&format!("bitcoin:{}?amount=", MAINNET_ADDRESS)));
+    assert!(uri.contains("message=coffee+%26+cake"));
+
+    let parsed = parse(&uri, Network::Bitcoin).unwrap();
+    assert_eq!(parsed.address, Some(mainnet_address()));
+    assert_eq!(parsed.amount_sats, Some(50_000));
+    assert_eq!(parsed.invoice, Some(invoice));
+    assert!(parsed.offer.is_none());
+  }
+
+  #[test]
+  fn parse_accepts_uppercase_scheme_and_bare_address() {
+    let parsed = parse(&format!("BITCOIN:{}", MAINNET_ADDRESS), Network::Bitcoin).unwrap();
+    assert_eq!(parsed.address, Some(mainnet_address()));
+    assert_eq!(parsed.amount_sats, None);
+    assert!(parsed.invoice.is_none());
+  }
+
+  #[test]
+  fn parse_rejects_non_bip21_input_without_panicking() {
+    assert!(parse("bitcoinë:abc", Network::Bitcoin).is_err());
+    assert!(parse("btc:", Network::Bitcoin).is_err());
+    assert!(parse("", Network::Bitcoin).is_err());
+  }
+
+  #[test]
+  fn parse_rejects_address_for_another_network() {
+    let uri = format!("bitcoin:{}?amount=0.001", TESTNET_ADDRESS);
+    assert!(parse(&uri, Network::Bitcoin).is_err());
+    assert!(parse(&uri, Network::Testnet).is_ok());
+  }
+
+  #[test]
+  fn parse_rejects_unknown_required_parameters() {
+    let uri = format!(
+      "bitcoin:{}?req-somethingyoudontunderstand=50",
+      MAINNET_ADDRESS
+    );
+    assert!(parse(&uri, Network::Bitcoin).is_err());
+    let uri = format!("bitcoin:{}?somethingelseyoudontget=999", MAINNET_ADDRESS);
+    assert!(parse(&uri, Network::Bitcoin).is_ok());
+  }
+
+  #[test]
+  fn parse_rejects_malformed_amount() {
+    let uri = format!("bitcoin:{}?amount=1,000", MAINNET_ADDRESS);
+    assert!(parse(&uri, Network::Bitcoin).is_err());
+  }
+
+  #[test]
+  fn only_falls_back_when_nothing_was_sent() {
+    assert!(nothing_sent(&NodeError::PaymentSendingFailed));
+    assert!(!nothing_sent(&NodeError::DuplicatePayment));
+  }
+}
@@ -0,0 +1,208 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+
+use ldk_node::lightning::routing::gossip::NodeId;
+use napi::Env;
+use napi::Task;
+use napi_derive::napi;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::utils::node_error;
+use crate::utils::ChannelInfo;
+use crate::utils::ChannelUpdateInfo;
+use crate::utils::Error;
+use crate::utils::NodeInfo;
+
+#[napi(string_enum = "lowercase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphExportFormat {
+  Json,
+  Dot,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct ExportNetworkGraphOptions {
+  #[napi(ts_type = "'json' | 'dot'")]
+  pub format: GraphExportFormat,
+  pub path: String,
+}
+
+/// Writes the graph on the libuv thread pool, as a large graph takes a while
+/// to walk and write out.
+pub struct ExportNetworkGraphTask {
+  graph: ldk_node::graph::NetworkGraph,
+  options: ExportNetworkGraphOptions,
+}
+
+impl ExportNetworkGraphTask {
+  pub fn new(graph: ldk_node::graph::NetworkGraph, options: ExportNetworkGraphOptions) -> Self {
+    ExportNetworkGraphTask { graph, options }
+  }
+}
+
+impl Task for ExportNetworkGraphTask {
+  type Output = bool;
+  type JsValue = bool;
+
+  fn compute(&mut self) -> napi::Result<bool> {
+    export_network_graph(&self.graph, &self.options)
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, e.reason))
+  }
+
+  fn resolve(&mut self, _env: Env, output: bool) -> napi::Result<bool> {
+    Ok(output)
+  }
+}
+
+pub fn export_network_graph(
+  graph: &ldk_node::graph::NetworkGraph,
+  options: &ExportNetworkGraphOptions,
+) -> Result<bool, Error> {
+  let file = File::create(&options.path).map_err(|e| node_error(e.to_string()))?;
+  let mut writer = BufWriter::new(file);
+  let written = match options.format {
+    GraphExportFormat::Json => write_json(graph, &mut writer),
+    GraphExportFormat::Dot => write_dot(graph, &mut writer),
+  };
+  match written.and_then(|()| writer.flush()) {
+    Ok(()) => Ok(true),
+    Err(e) => Err(node_error(e.to_string())),
+  }
+}
+
+fn nodes(graph: &ldk_node::graph::NetworkGraph) -> impl Iterator<Item = NodeInfo> + '_ {
+  graph
+    .list_nodes()
+    .into_iter()
+    .filter_map(|node_id: NodeId| {
+      graph
+        .node(&node_id)
+        .map(|node| NodeInfo::new(node_id, node))
+    })
+}
+
+fn channels(graph: &ldk_node::graph::NetworkGraph) -> impl Iterator<Item = ChannelInfo> + '_ {
+  graph.list_channels().into_iter().filter_map(|scid| {
+    graph
+      .channel(scid)
+      .map(|channel| ChannelInfo::new(scid, channel))
+  })
+}
+
+fn update_json(update: &Option<ChannelUpdateInfo>) -> Value {
+  match update {
+    Some(update) => json!({
+      "last_update": update.last_update,
+      "enabled": update.enabled,
+      "cltv_expiry_delta": update.cltv_expiry_delta,
+      "htlc_minimum_msat": update.htlc_minimum_msat,
+      "htlc_maximum_msat": update.htlc_maximum_msat,
+      "fee_base_msat": update.fees.base_msat,
+      "fee_proportional_millionths": update.fees.proportional_millionths,
+    }),
+    None => Value::Null,
+  }
+}
+
+fn write_json<W: Write>(
+  graph: &ldk_node::graph::NetworkGraph,
+  writer: &mut W,
+) -> std::io::Result<()> {
+  writer.write_all(b"{\"nodes\":[")?;
+  for (i, node) in nodes(graph).enumerate() {
+    if i > 0 {
+      writer.write_all(b",")?;
+    }
+    let announcement = match &node.announcement_info {
+      Some(info) => json!({
+        "last_update": info.last_update,
+        "alias": info.alias,
+        "color": info.color,
+        "addresses": info.addresses,
+        "features": info.features,
+      }),
+      None => Value::Null,
+    };
+    let value = json!({
+      "node_id": node.node_id,
+      "channels": node.channels,
+      "announcement_info": announcement,
+    });
+    serde_json::to_writer(&mut *writer, &value)?;
+  }
+  writer.write_all(b"],\"channels\":[")?;
+  for (i, channel) in channels(graph).enumerate() {
+    if i > 0 {
+      writer.write_all(b",")?;
+    }
+    let value = json!({
+      "short_channel_id": channel.short_channel_id,
+      "node_one": channel.node_one,
+      "node_two": channel.node_two,
+      "capacity_sats": channel.capacity_sats,
+      "one_to_two": update_json(&channel.one_to_two),
+      "two_to_one": update_json(&channel.two_to_one),
+    });
+    serde_json::to_writer(&mut *writer, &value)?;
+  }
+  writer.write_all(b"]}")
+}
+
+fn dot_escape(value: &str) -> String {
+  value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn dot_policy(update: &Option<ChannelUpdateInfo>) -> String {
+  match update {
+    Some(update) => format!(
+      "{}msat+{}ppm cltv {}{}",
+      update.fees.base_msat,
+      update.fees.proportional_millionths,
+      update.cltv_expiry_delta,
+      if update.enabled { "" } else { " disabled" }
+    ),
+    None => "-".to_string(),
+  }
+}
+
+fn write_dot<W: Write>(
+  graph: &ldk_node::graph::NetworkGraph,
+  writer: &mut W,
+) -> std::io::Result<()> {
+  writeln!(writer, "graph network {{")?;
+  for node in nodes(graph) {
+    match &node.announcement_info {
+      Some(info) => writeln!(
+        writer,
+        "  \"{}\" [label=\"{}\", color=\"{}\", addresses=\"{}\", features=\"{}\", last_update={}];",
+        node.node_id,
+        dot_escape(&info.alias),
+        info.color,
+        dot_escape(&info.addresses.join(" ")),
+        info.features,
+        info.last_update
+      )?,
+      None => writeln!(writer, "  \"{}\";", node.node_id)?,
+    }
+  }
+  for channel in channels(graph) {
+    let capacity = match channel.capacity_sats {
+      Some(capacity) => capacity.to_string(),
+      None => "unknown".to_string(),
+    };
+    writeln!(
+      writer,
+      "  \"{}\" -- \"{}\" [label=\"{}\", capacity_sats=\"{}\", one_to_two=\"{}\", two_to_one=\"{}\"];",
+      channel.node_one,
+      channel.node_two,
+      channel.short_channel_id,
+      capacity,
+      dot_policy(&channel.one_to_two),
+      dot_policy(&channel.two_to_one)
+    )?;
+  }
+  writeln!(writer, "}}")
+}
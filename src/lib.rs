@@ -1,30 +1,59 @@
 #![deny(clippy::all)]
+pub mod bip21;
+pub mod graph_export;
+pub mod lnurl;
+pub mod metrics;
 pub mod utils;
 
+use bip21::UnifiedPaymentResult;
+use graph_export::ExportNetworkGraphOptions;
+use graph_export::ExportNetworkGraphTask;
 use ldk_node::bip39::Mnemonic;
-use ldk_node::io::SqliteStore;
+use ldk_node::lightning::routing::gossip::NodeId;
 use ldk_node::lightning_invoice::Bolt11Invoice;
-use napi::Error;
+use lnurl::LnurlTask;
+use metrics::MetricsServer;
+use metrics::SyncStats;
+use napi::bindgen_prelude::AsyncTask;
+use napi::bindgen_prelude::BigInt;
+use napi::CleanupEnvHook;
+use napi::Env;
 use napi_derive::napi;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::Weak;
+use std::time::Instant;
+use utils::amount_from_nodejs;
+use utils::lifecycle_error;
 use utils::node_error;
 use utils::Address;
+use utils::BalanceDetails;
 use utils::ChannelConfig;
 use utils::ChannelDetails;
 use utils::ChannelId;
+use utils::ChannelInfo;
+use utils::Error;
+use utils::LifecycleError;
+use utils::Offer;
 use utils::PaymentDetails;
 use utils::PaymentHash;
+use utils::PaymentId;
+use utils::Refund;
 use utils::Txid;
 
 use utils::LogLevel;
 use utils::Network;
+use utils::NodeInfo;
+use utils::NodeLifecycleState;
+use utils::NodeStatus;
 use utils::PeerDetails;
 
 use crate::utils::get_event;
 
 #[napi]
 pub struct NetAddress {
-  inner: ldk_node::NetAddress,
+  inner: ldk_node::lightning::ln::msgs::SocketAddress,
 }
 
 #[napi]
@@ -33,7 +62,7 @@ impl NetAddress {
   pub fn new(ipv4: String, port: u32) -> Result<Self, Error> {
     let addr = format!("{}:{}", ipv4, port).to_string();
     Ok(NetAddress {
-      inner: ldk_node::NetAddress::from_str(&addr).unwrap(),
+      inner: ldk_node::lightning::ln::msgs::SocketAddress::from_str(&addr).unwrap(),
     })
   }
 }
@@ -67,7 +96,6 @@ impl Config {
     log_dir_path: Option<String>,
     network: Network,
     listening_address: &NetAddress,
-    default_cltv_expiry_delta: u32,
     onchain_wallet_sync_interval_secs: u32,
     wallet_sync_interval_secs: u32,
     fee_rate_cache_update_interval_secs: u32,
@@ -78,14 +106,14 @@ impl Config {
       storage_dir_path: storage_dir_path,
       log_dir_path: log_dir_path,
       network: network.into(),
-      listening_address: Some(listening_address.inner.to_owned()),
-      default_cltv_expiry_delta: default_cltv_expiry_delta,
+      listening_addresses: Some(vec![listening_address.inner.to_owned()]),
       onchain_wallet_sync_interval_secs: u64::from(onchain_wallet_sync_interval_secs),
       wallet_sync_interval_secs: u64::from(wallet_sync_interval_secs),
       fee_rate_cache_update_interval_secs: u64::from(fee_rate_cache_update_interval_secs),
       trusted_peers_0conf: Vec::new(),
       probing_liquidity_limit_multiplier: probing_liquidity_limit_multiplier as u64,
       log_level: log_level.into(),
+      ..Default::default()
     };
     Ok(Config { inner: config })
   }
@@ -94,6 +122,8 @@ impl Config {
 #[napi]
 pub struct Builder {
   inner: ldk_node::Builder,
+  network: ldk_node::bitcoin::Network,
+  metrics_port: Option<u16>,
 }
 
 #[napi]
@@ -102,6 +132,8 @@ impl Builder {
   pub fn new() -> Self {
     Builder {
       inner: ldk_node::Builder::new(),
+      network: ldk_node::bitcoin::Network::Bitcoin,
+      metrics_port: None,
     }
   }
 
@@ -109,6 +141,8 @@ impl Builder {
   pub fn from_config(config: &Config) -> Self {
     Builder {
       inner: ldk_node::Builder::from_config(config.inner.to_owned()),
+      network: config.inner.network,
+      metrics_port: None,
     }
   }
 
@@ -174,15 +208,34 @@ impl Builder {
 
   #[napi]
   pub fn set_network(&mut self, network: Network) -> Result<bool, Error> {
-    self.inner.set_network(network.into());
+    self.network = network.into();
+    self.inner.set_network(self.network);
     Ok(true)
   }
 
   #[napi]
   pub fn set_listening_address(&mut self, listening_address: &NetAddress) -> Result<bool, Error> {
-    self
+    match self
       .inner
-      .set_listening_address(listening_address.inner.to_owned());
+      .set_listening_addresses(vec![listening_address.inner.to_owned()])
+    {
+      Ok(_builder) => Ok(true),
+      Err(e) => Err(node_error(e.to_string())),
+    }
+  }
+
+  #[napi]
+  pub fn set_liquidity_source_lsps2(
+    &mut self,
+    node_id: &PublicKey,
+    address: &NetAddress,
+    token: Option<String>,
+  ) -> Result<bool, Error> {
+    self.inner.set_liquidity_source_lsps2(
+      address.inner.to_owned(),
+      node_id.inner.to_owned(),
+      token,
+    );
     Ok(true)
   }
 
@@ -193,78 +246,265 @@ impl Builder {
   }
 
   #[napi]
-  pub fn build(&mut self) -> Result<Node, Error> {
+  pub fn set_metrics_port(&mut self, port: u16) -> Result<bool, Error> {
+    self.metrics_port = Some(port);
+    Ok(true)
+  }
+
+  #[napi]
+  pub fn build(&mut self, mut env: Env) -> Result<Node, Error> {
     let builded = self.inner.build();
     match builded {
-      Ok(node) => Ok(Node { inner: node }),
+      Ok(node) => {
+        let inner = Arc::new(node);
+        let runtime = Arc::new(NodeRuntime {
+          inner: Arc::clone(&inner),
+          state: Mutex::new(NodeLifecycleState::Built),
+          metrics_server: Mutex::new(None),
+        });
+        let cleanup_hook = env
+          .add_env_cleanup_hook(Arc::downgrade(&runtime), |runtime| {
+            if let Some(runtime) = runtime.upgrade() {
+              runtime.shutdown_if_running();
+            }
+          })
+          .map_err(|e| node_error(e.reason))?;
+        Ok(Node {
+          inner,
+          runtime,
+          env,
+          cleanup_hook: Some(cleanup_hook),
+          network: self.network,
+          metrics_port: self.metrics_port,
+          sync_stats: Arc::new(SyncStats::default()),
+        })
+      }
       Err(e) => Err(node_error(e.to_string())),
     }
   }
 }
 
+#[napi]
+pub struct NetworkGraph {
+  inner: ldk_node::graph::NetworkGraph,
+}
+
+#[napi]
+impl NetworkGraph {
+  #[napi]
+  pub fn list_channels(&self) -> Vec<u64> {
+    self.inner.list_channels()
+  }
+
+  #[napi]
+  pub fn channel(&self, short_channel_id: BigInt) -> Result<Option<ChannelInfo>, Error> {
+    let (sign_bit, scid, lossless) = short_channel_id.get_u64();
+    if sign_bit || !lossless {
+      return Err(node_error(
+        "Short channel id must be an unsigned 64-bit integer".to_string(),
+      ));
+    }
+    Ok(
+      self
+        .inner
+        .channel(scid)
+        .map(|channel| ChannelInfo::new(scid, channel)),
+    )
+  }
+
+  #[napi]
+  pub fn list_nodes(&self) -> Vec<String> {
+    let response_list = self.inner.list_nodes();
+    let mut list = Vec::new();
+    for item in &response_list {
+      list.push(item.to_string());
+    }
+    list
+  }
+
+  #[napi]
+  pub fn node(&self, node_id: &PublicKey) -> Option<NodeInfo> {
+    let node_id = NodeId::from_pubkey(&node_id.inner);
+    self
+      .inner
+      .node(&node_id)
+      .map(|node| NodeInfo::new(node_id, node))
+  }
+}
+
+/// The parts of a running node that both `Node` and its env cleanup hook
+/// need in order to shut it down.
+struct NodeRuntime {
+  inner: Arc<ldk_node::Node>,
+  state: Mutex<NodeLifecycleState>,
+  metrics_server: Mutex<Option<MetricsServer>>,
+}
+
+impl NodeRuntime {
+  fn shutdown(&self, state: &mut NodeLifecycleState) -> Result<(), ldk_node::NodeError> {
+    if let Some(mut server) = self.metrics_server.lock().unwrap().take() {
+      server.stop();
+    }
+    *state = NodeLifecycleState::Stopped;
+    self.inner.stop()
+  }
+
+  fn shutdown_if_running(&self) {
+    let mut state = self.state.lock().unwrap();
+    if *state == NodeLifecycleState::Running {
+      let _ = self.shutdown(&mut state);
+    }
+  }
+}
+
 #[napi]
 pub struct Node {
-  inner: ldk_node::Node<SqliteStore>,
+  inner: Arc<ldk_node::Node>,
+  runtime: Arc<NodeRuntime>,
+  env: Env,
+  cleanup_hook: Option<CleanupEnvHook<Weak<NodeRuntime>>>,
+  network: ldk_node::bitcoin::Network,
+  metrics_port: Option<u16>,
+  sync_stats: Arc<SyncStats>,
+}
+
+impl Node {
+  fn ensure_running(&self) -> Result<(), Error> {
+    match *self.runtime.state.lock().unwrap() {
+      NodeLifecycleState::Running => Ok(()),
+      _ => Err(lifecycle_error(LifecycleError::NotRunning)),
+    }
+  }
+
+  fn user_channel_id(
+    &self,
+    channel_id: ChannelId,
+    counterparty_node_id: &PublicKey,
+  ) -> Result<ldk_node::UserChannelId, Error> {
+    let channel_id = ChannelId::from_nodejs(channel_id);
+    match self.inner.list_channels().into_iter().find(|channel| {
+      channel.channel_id == channel_id && channel.counterparty_node_id == counterparty_node_id.inner
+    }) {
+      Some(channel) => Ok(channel.user_channel_id),
+      None => Err(node_error(format!("Unknown channel: {}", channel_id))),
+    }
+  }
+
+  fn lnurl_client(&self) -> lnurl::Client {
+    lnurl::Client::new(self.network == ldk_node::bitcoin::Network::Regtest)
+  }
+}
+
+impl Drop for Node {
+  fn drop(&mut self) {
+    if let Some(hook) = self.cleanup_hook.take() {
+      let _ = self.env.remove_env_cleanup_hook(hook);
+    }
+    self.runtime.shutdown_if_running();
+  }
 }
 
 #[napi]
 impl Node {
   #[napi]
-  pub fn start(&mut self) -> Result<bool, Error> {
-    match self.inner.start() {
-      Ok(()) => Ok(true),
-      Err(e) => Err(node_error(e.to_string())),
+  pub fn start(&self) -> Result<bool, Error> {
+    let mut state = self.runtime.state.lock().unwrap();
+    if *state == NodeLifecycleState::Running {
+      return Err(lifecycle_error(LifecycleError::AlreadyRunning));
+    }
+    if let Err(e) = self.inner.start() {
+      return Err(node_error(e.to_string()));
     }
+    *state = NodeLifecycleState::Running;
+    if let Some(port) = self.metrics_port {
+      match MetricsServer::start(Arc::clone(&self.inner), Arc::clone(&self.sync_stats), port) {
+        Ok(server) => *self.runtime.metrics_server.lock().unwrap() = Some(server),
+        Err(e) => {
+          let _ = self.runtime.shutdown(&mut state);
+          return Err(node_error(format!("Failed to start metrics server: {}", e)));
+        }
+      }
+    }
+    Ok(true)
   }
 
   #[napi]
-  pub fn stop(&mut self) -> Result<bool, Error> {
-    match self.inner.stop() {
+  pub fn stop(&self) -> Result<bool, Error> {
+    let mut state = self.runtime.state.lock().unwrap();
+    if *state != NodeLifecycleState::Running {
+      return Err(lifecycle_error(LifecycleError::NotRunning));
+    }
+    match self.runtime.shutdown(&mut state) {
       Ok(()) => Ok(true),
       Err(e) => Err(node_error(e.to_string())),
     }
   }
 
   #[napi]
-  pub fn sync_wallets(&mut self) -> Result<bool, Error> {
-    match self.inner.sync_wallets() {
+  pub fn lifecycle_state(&self) -> NodeLifecycleState {
+    *self.runtime.state.lock().unwrap()
+  }
+
+  #[napi]
+  pub fn status(&self) -> NodeStatus {
+    let connected_peers = self
+      .inner
+      .list_peers()
+      .iter()
+      .filter(|peer| peer.is_connected)
+      .count();
+    let usable_channels = self
+      .inner
+      .list_channels()
+      .iter()
+      .filter(|channel| channel.is_usable)
+      .count();
+    NodeStatus::new(
+      self.inner.status(),
+      connected_peers as u32,
+      usable_channels as u32,
+    )
+  }
+
+  #[napi]
+  pub fn sync_wallets(&self) -> Result<bool, Error> {
+    self.ensure_running()?;
+    let started = Instant::now();
+    let synced = self.inner.sync_wallets();
+    self.sync_stats.record(started.elapsed(), synced.is_ok());
+    match synced {
       Ok(()) => Ok(true),
       Err(e) => Err(node_error(e.to_string())),
     }
   }
 
   #[napi]
-  pub fn node_id(&mut self) -> String {
+  pub fn node_id(&self) -> String {
     self.inner.node_id().to_owned().to_string()
   }
 
   #[napi]
-  pub fn listening_address(&mut self) -> Option<String> {
-    let address = self.inner.listening_address();
-    if address.is_none() {
-      None
-    } else {
-      Some(address.unwrap().to_owned().to_string())
-    }
+  pub fn listening_address(&self) -> Option<String> {
+    let addresses = self.inner.listening_addresses()?;
+    addresses.first().map(|address| address.to_string())
   }
 
   #[napi]
-  pub fn new_onchain_address(&mut self) -> Result<Address, Error> {
-    match self.inner.new_onchain_address() {
+  pub fn new_onchain_address(&self) -> Result<Address, Error> {
+    match self.inner.onchain_payment().new_address() {
       Ok(address) => Ok(Address::from_ldk_node(address)),
       Err(e) => Err(node_error(e.to_string())),
     }
   }
 
   #[napi]
-  pub fn send_to_onchain_address(
-    &mut self,
-    address: Address,
-    amount_msat: u32,
-  ) -> Result<Txid, Error> {
+  pub fn send_to_onchain_address(&self, address: Address, amount_msat: u32) -> Result<Txid, Error> {
+    self.ensure_running()?;
+    let address = Address::from_nodejs(&address, self.network)?;
     match self
       .inner
-      .send_to_onchain_address(&Address::from_nodejs(&address), amount_msat as u64)
+      .onchain_payment()
+      .send_to_address(&address, amount_msat as u64)
     {
       Ok(txid) => Ok(Txid::from_ldk_node(txid)),
       Err(e) => Err(node_error(e.to_string())),
@@ -272,39 +512,38 @@ impl Node {
   }
 
   #[napi]
-  pub fn send_all_to_onchain_address(&mut self, address: Address) -> Result<Txid, Error> {
-    match self
-      .inner
-      .send_all_to_onchain_address(&Address::from_nodejs(&address))
-    {
+  pub fn send_all_to_onchain_address(&self, address: Address) -> Result<Txid, Error> {
+    self.ensure_running()?;
+    let address = Address::from_nodejs(&address, self.network)?;
+    match self.inner.onchain_payment().send_all_to_address(&address) {
       Ok(txid) => Ok(Txid::from_ldk_node(txid)),
       Err(e) => Err(node_error(e.to_string())),
     }
   }
 
   #[napi]
-  pub fn spendable_onchain_balance_sats(&mut self) -> Result<u64, Error> {
-    match self.inner.spendable_onchain_balance_sats() {
-      Ok(sats) => Ok(sats),
-      Err(e) => Err(node_error(e.to_string())),
-    }
+  pub fn spendable_onchain_balance_sats(&self) -> Result<u64, Error> {
+    Ok(self.inner.list_balances().spendable_onchain_balance_sats)
   }
 
   #[napi]
-  pub fn total_onchain_balance_sats(&mut self) -> Result<u64, Error> {
-    match self.inner.total_onchain_balance_sats() {
-      Ok(sats) => Ok(sats),
-      Err(e) => Err(node_error(e.to_string())),
-    }
+  pub fn total_onchain_balance_sats(&self) -> Result<u64, Error> {
+    Ok(self.inner.list_balances().total_onchain_balance_sats)
+  }
+
+  #[napi]
+  pub fn list_balances(&self) -> BalanceDetails {
+    BalanceDetails::new(self.inner.list_balances())
   }
 
   #[napi]
   pub fn connect(
-    &mut self,
+    &self,
     node_id: &PublicKey,
     address: &NetAddress,
     persist: bool,
   ) -> Result<bool, Error> {
+    self.ensure_running()?;
     match self
       .inner
       .connect(node_id.inner.to_owned(), address.inner.to_owned(), persist)
@@ -315,7 +554,8 @@ impl Node {
   }
 
   #[napi]
-  pub fn disconnect(&mut self, counterparty_node_id: &PublicKey) -> Result<bool, Error> {
+  pub fn disconnect(&self, counterparty_node_id: &PublicKey) -> Result<bool, Error> {
+    self.ensure_running()?;
     match self.inner.disconnect(counterparty_node_id.inner.to_owned()) {
       Ok(()) => Ok(true),
       Err(e) => Err(node_error(e.to_string())),
@@ -324,7 +564,7 @@ impl Node {
 
   #[napi]
   pub fn connect_open_channel(
-    &mut self,
+    &self,
     node_id: &PublicKey,
     address: &NetAddress,
     channel_amount_sats: u32,
@@ -332,6 +572,7 @@ impl Node {
     channel_config: Option<&ChannelConfig>,
     announce_channel: bool,
   ) -> Result<bool, Error> {
+    self.ensure_running()?;
     let remote_msats;
     if push_to_counterparty_msat.is_none() {
       remote_msats = None
@@ -352,37 +593,77 @@ impl Node {
       ch_config,
       announce_channel,
     ) {
-      Ok(()) => Ok(true),
+      Ok(_user_channel_id) => Ok(true),
       Err(e) => Err(node_error(e.to_string())),
     }
   }
 
   #[napi]
   pub fn close_channel(
-    &mut self,
+    &self,
     channel_id: ChannelId,
     counterparty_node_id: &PublicKey,
   ) -> Result<bool, Error> {
-    match self.inner.close_channel(
-      &ChannelId::from_nodejs(channel_id),
-      counterparty_node_id.inner.to_owned(),
-    ) {
+    self.ensure_running()?;
+    let user_channel_id = self.user_channel_id(channel_id, counterparty_node_id)?;
+    match self
+      .inner
+      .close_channel(&user_channel_id, counterparty_node_id.inner.to_owned())
+    {
       Ok(()) => Ok(true),
       Err(e) => Err(node_error(e.to_string())),
     }
   }
 
+  #[napi]
+  pub fn force_close_channel(
+    &self,
+    channel_id: ChannelId,
+    counterparty_node_id: &PublicKey,
+  ) -> Result<bool, Error> {
+    self.ensure_running()?;
+    let user_channel_id = self.user_channel_id(channel_id, counterparty_node_id)?;
+    match self
+      .inner
+      .force_close_channel(&user_channel_id, counterparty_node_id.inner.to_owned())
+    {
+      Ok(()) => Ok(true),
+      Err(e) => Err(node_error(e.to_string())),
+    }
+  }
+
+  #[napi]
+  pub fn force_close_all_channels(&self) -> Result<bool, Error> {
+    self.ensure_running()?;
+    let mut errors = Vec::new();
+    for channel in self.inner.list_channels() {
+      if let Err(e) = self
+        .inner
+        .force_close_channel(&channel.user_channel_id, channel.counterparty_node_id)
+      {
+        errors.push(format!("{}: {}", channel.channel_id, e));
+      }
+    }
+    if errors.is_empty() {
+      Ok(true)
+    } else {
+      Err(node_error(errors.join(", ")))
+    }
+  }
+
   #[napi]
   pub fn receive_payment(
-    &mut self,
+    &self,
     amount_msat: u32,
     description: String,
     expiry_secs: u32,
   ) -> Result<String, Error> {
+    self.ensure_running()?;
     let desc = description.as_str();
     match self
       .inner
-      .receive_payment(u64::from(amount_msat), desc, expiry_secs)
+      .bolt11_payment()
+      .receive(u64::from(amount_msat), desc, expiry_secs)
     {
       Ok(invoice) => Ok(invoice.to_string()),
       Err(e) => Err(node_error(e.to_string())),
@@ -391,14 +672,16 @@ impl Node {
 
   #[napi]
   pub fn receive_variable_amount_payment(
-    &mut self,
+    &self,
     description: String,
     expiry_secs: u32,
   ) -> Result<String, Error> {
+    self.ensure_running()?;
     let desc = description.as_str();
     match self
       .inner
-      .receive_variable_amount_payment(desc, expiry_secs)
+      .bolt11_payment()
+      .receive_variable_amount(desc, expiry_secs)
     {
       Ok(invoice) => Ok(invoice.to_string()),
       Err(e) => Err(node_error(e.to_string())),
@@ -406,11 +689,103 @@ impl Node {
   }
 
   #[napi]
-  pub fn send_payment(&mut self, invoice: String) -> Result<PaymentHash, Error> {
+  pub fn receive_payment_via_jit_channel(
+    &self,
+    amount_msat: i64,
+    description: String,
+    expiry_secs: u32,
+    max_total_lsp_fee_limit_msat: Option<i64>,
+  ) -> Result<String, Error> {
+    self.ensure_running()?;
+    let amount_msat = amount_from_nodejs(amount_msat)?;
+    let max_total_lsp_fee_limit_msat = max_total_lsp_fee_limit_msat
+      .map(amount_from_nodejs)
+      .transpose()?;
+    let desc = description.as_str();
+    match self.inner.bolt11_payment().receive_via_jit_channel(
+      amount_msat,
+      desc,
+      expiry_secs,
+      max_total_lsp_fee_limit_msat,
+    ) {
+      Ok(invoice) => Ok(invoice.to_string()),
+      Err(e) => Err(node_error(e.to_string())),
+    }
+  }
+
+  #[napi]
+  pub fn receive_variable_amount_payment_via_jit_channel(
+    &self,
+    description: String,
+    expiry_secs: u32,
+    max_proportional_lsp_fee_limit_ppm_msat: Option<i64>,
+  ) -> Result<String, Error> {
+    self.ensure_running()?;
+    let max_proportional_lsp_fee_limit_ppm_msat = max_proportional_lsp_fee_limit_ppm_msat
+      .map(amount_from_nodejs)
+      .transpose()?;
+    let desc = description.as_str();
+    match self
+      .inner
+      .bolt11_payment()
+      .receive_variable_amount_via_jit_channel(
+        desc,
+        expiry_secs,
+        max_proportional_lsp_fee_limit_ppm_msat,
+      ) {
+      Ok(invoice) => Ok(invoice.to_string()),
+      Err(e) => Err(node_error(e.to_string())),
+    }
+  }
+
+  #[napi]
+  pub fn receive_unified(
+    &self,
+    amount_sats: u32,
+    description: String,
+    expiry_secs: u32,
+  ) -> Result<String, Error> {
+    self.ensure_running()?;
+    let address = match self.inner.onchain_payment().new_address() {
+      Ok(address) => address,
+      Err(e) => return Err(node_error(e.to_string())),
+    };
+    let desc = description.as_str();
+    let amount_msat = u64::from(amount_sats) * 1000;
+    let invoice = match self
+      .inner
+      .bolt11_payment()
+      .receive(amount_msat, desc, expiry_secs)
+    {
+      Ok(invoice) => invoice,
+      Err(e) => return Err(node_error(e.to_string())),
+    };
+    // Leave out `lno=` rather than failing the whole URI when no offer can
+    // be created.
+    let offer = self.inner.bolt12_payment().receive(amount_msat, desc).ok();
+    Ok(bip21::build(
+      &address,
+      u64::from(amount_sats),
+      desc,
+      &invoice,
+      offer.as_ref(),
+    ))
+  }
+
+  #[napi]
+  pub fn pay_unified(&self, uri: String) -> Result<UnifiedPaymentResult, Error> {
+    self.ensure_running()?;
+    let parsed = bip21::parse(&uri, self.network)?;
+    bip21::pay(&self.inner, &parsed)
+  }
+
+  #[napi]
+  pub fn send_payment(&self, invoice: String) -> Result<PaymentId, Error> {
+    self.ensure_running()?;
     let invoice_struct = Bolt11Invoice::from_str(&invoice);
     match invoice_struct {
-      Ok(invoice) => match self.inner.send_payment(&invoice) {
-        Ok(payment_hash) => Ok(PaymentHash::from_ldk_node(payment_hash)),
+      Ok(invoice) => match self.inner.bolt11_payment().send(&invoice) {
+        Ok(payment_id) => Ok(PaymentId::from_ldk_node(payment_id)),
         Err(e) => Err(node_error(e.to_string())),
       },
       Err(e) => Err(node_error(e.to_string())),
@@ -419,40 +794,232 @@ impl Node {
 
   #[napi]
   pub fn send_payment_using_amount(
-    &mut self,
+    &self,
     invoice: String,
     amount_msat: u32,
-  ) -> Result<PaymentHash, Error> {
+  ) -> Result<PaymentId, Error> {
+    self.ensure_running()?;
     let invoice_struct = Bolt11Invoice::from_str(&invoice);
     match invoice_struct {
       Ok(invoice) => match self
         .inner
-        .send_payment_using_amount(&invoice, u64::from(amount_msat))
+        .bolt11_payment()
+        .send_using_amount(&invoice, u64::from(amount_msat))
       {
-        Ok(payment_hash) => Ok(PaymentHash::from_ldk_node(payment_hash)),
+        Ok(payment_id) => Ok(PaymentId::from_ldk_node(payment_id)),
         Err(e) => Err(node_error(e.to_string())),
       },
       Err(e) => Err(node_error(e.to_string())),
     }
   }
 
+  #[napi]
+  pub fn receive_offer(&self, amount_msat: i64, description: String) -> Result<Offer, Error> {
+    self.ensure_running()?;
+    let amount_msat = amount_from_nodejs(amount_msat)?;
+    match self
+      .inner
+      .bolt12_payment()
+      .receive(amount_msat, description.as_str())
+    {
+      Ok(offer) => Ok(Offer::from_ldk_node(offer)),
+      Err(e) => Err(node_error(e.to_string())),
+    }
+  }
+
+  #[napi]
+  pub fn receive_variable_amount_offer(&self, description: String) -> Result<Offer, Error> {
+    self.ensure_running()?;
+    match self
+      .inner
+      .bolt12_payment()
+      .receive_variable_amount(description.as_str())
+    {
+      Ok(offer) => Ok(Offer::from_ldk_node(offer)),
+      Err(e) => Err(node_error(e.to_string())),
+    }
+  }
+
+  #[napi]
+  pub fn send_offer_payment(
+    &self,
+    offer: String,
+    payer_note: Option<String>,
+  ) -> Result<PaymentId, Error> {
+    self.ensure_running()?;
+    let offer = Offer::from_nodejs(&offer)?;
+    match self.inner.bolt12_payment().send(&offer, payer_note) {
+      Ok(payment_id) => Ok(PaymentId::from_ldk_node(payment_id)),
+      Err(e) => Err(node_error(e.to_string())),
+    }
+  }
+
+  #[napi]
+  pub fn send_offer_payment_using_amount(
+    &self,
+    offer: String,
+    amount_msat: i64,
+    payer_note: Option<String>,
+  ) -> Result<PaymentId, Error> {
+    self.ensure_running()?;
+    let offer = Offer::from_nodejs(&offer)?;
+    let amount_msat = amount_from_nodejs(amount_msat)?;
+    match self
+      .inner
+      .bolt12_payment()
+      .send_using_amount(&offer, payer_note, amount_msat)
+    {
+      Ok(payment_id) => Ok(PaymentId::from_ldk_node(payment_id)),
+      Err(e) => Err(node_error(e.to_string())),
+    }
+  }
+
+  #[napi]
+  pub fn initiate_refund(&self, amount_msat: i64, expiry_secs: u32) -> Result<Refund, Error> {
+    self.ensure_running()?;
+    let amount_msat = amount_from_nodejs(amount_msat)?;
+    match self
+      .inner
+      .bolt12_payment()
+      .initiate_refund(amount_msat, expiry_secs)
+    {
+      Ok(refund) => Ok(Refund::from_ldk_node(refund)),
+      Err(e) => Err(node_error(e.to_string())),
+    }
+  }
+
+  #[napi]
+  pub fn request_refund_payment(&self, refund: String) -> Result<PaymentHash, Error> {
+    self.ensure_running()?;
+    let refund = Refund::from_nodejs(&refund)?;
+    match self.inner.bolt12_payment().request_refund_payment(&refund) {
+      Ok(invoice) => Ok(PaymentHash::from_ldk_node(invoice.payment_hash())),
+      Err(e) => Err(node_error(e.to_string())),
+    }
+  }
+
   #[napi]
   pub fn send_spontaneous_payment(
-    &mut self,
+    &self,
     amount_msat: u32,
     node_id: &PublicKey,
-  ) -> Result<PaymentHash, Error> {
+  ) -> Result<PaymentId, Error> {
+    self.ensure_running()?;
+    match self
+      .inner
+      .spontaneous_payment()
+      .send(u64::from(amount_msat), node_id.inner.to_owned())
+    {
+      Ok(payment_id) => Ok(PaymentId::from_ldk_node(payment_id)),
+      Err(e) => Err(node_error(e.to_string())),
+    }
+  }
+
+  #[napi]
+  pub fn send_payment_probes(&self, invoice: String) -> Result<bool, Error> {
+    self.ensure_running()?;
+    let invoice_struct = Bolt11Invoice::from_str(&invoice);
+    match invoice_struct {
+      Ok(invoice) => match self.inner.bolt11_payment().send_probes(&invoice) {
+        Ok(()) => Ok(true),
+        Err(e) => Err(node_error(e.to_string())),
+      },
+      Err(e) => Err(node_error(e.to_string())),
+    }
+  }
+
+  #[napi]
+  pub fn send_spontaneous_payment_probes(
+    &self,
+    amount_msat: i64,
+    node_id: &PublicKey,
+  ) -> Result<bool, Error> {
+    self.ensure_running()?;
+    let amount_msat = amount_from_nodejs(amount_msat)?;
     match self
       .inner
-      .send_spontaneous_payment(u64::from(amount_msat), node_id.inner.to_owned())
+      .spontaneous_payment()
+      .send_probes(amount_msat, node_id.inner.to_owned())
     {
-      Ok(payment_hash) => Ok(PaymentHash::from_ldk_node(payment_hash)),
+      Ok(()) => Ok(true),
       Err(e) => Err(node_error(e.to_string())),
     }
   }
 
+  #[napi(ts_return_type = "Promise<PaymentId>")]
+  pub fn lnurl_pay(
+    &self,
+    lnurl: String,
+    amount_msat: i64,
+    comment: Option<String>,
+  ) -> Result<AsyncTask<LnurlTask<PaymentId>>, Error> {
+    self.ensure_running()?;
+    let amount_msat = amount_from_nodejs(amount_msat)?;
+    let client = self.lnurl_client();
+    let inner = Arc::clone(&self.inner);
+    Ok(LnurlTask::spawn(move || {
+      let url = client.decode(&lnurl)?;
+      let payment_id = lnurl::pay(&client, &inner, url.as_str(), amount_msat, comment)?;
+      Ok(PaymentId::from_ldk_node(payment_id))
+    }))
+  }
+
+  #[napi(ts_return_type = "Promise<PaymentId>")]
+  pub fn pay_lightning_address(
+    &self,
+    address: String,
+    amount_msat: i64,
+    comment: Option<String>,
+  ) -> Result<AsyncTask<LnurlTask<PaymentId>>, Error> {
+    self.ensure_running()?;
+    let amount_msat = amount_from_nodejs(amount_msat)?;
+    let client = self.lnurl_client();
+    let inner = Arc::clone(&self.inner);
+    Ok(LnurlTask::spawn(move || {
+      let url = lnurl::lightning_address_url(&address)?;
+      let payment_id = lnurl::pay(&client, &inner, url.as_str(), amount_msat, comment)?;
+      Ok(PaymentId::from_ldk_node(payment_id))
+    }))
+  }
+
+  #[napi(ts_return_type = "Promise<PaymentId>")]
+  pub fn lnurl_withdraw(
+    &self,
+    lnurl: String,
+    amount_msat: Option<i64>,
+    description: Option<String>,
+    expiry_secs: u32,
+  ) -> Result<AsyncTask<LnurlTask<PaymentId>>, Error> {
+    self.ensure_running()?;
+    let amount_msat = amount_msat.map(amount_from_nodejs).transpose()?;
+    let client = self.lnurl_client();
+    let inner = Arc::clone(&self.inner);
+    Ok(LnurlTask::spawn(move || {
+      let url = client.decode(&lnurl)?;
+      let payment_id = lnurl::withdraw(
+        &client,
+        &inner,
+        url.as_str(),
+        amount_msat,
+        description,
+        expiry_secs,
+      )?;
+      Ok(PaymentId::from_ldk_node(payment_id))
+    }))
+  }
+
+  #[napi(ts_return_type = "Promise<string>")]
+  pub fn lnurl_auth(&self, lnurl: String) -> AsyncTask<LnurlTask<String>> {
+    let client = self.lnurl_client();
+    let inner = Arc::clone(&self.inner);
+    LnurlTask::spawn(move || {
+      let url = client.decode(&lnurl)?;
+      lnurl::auth(&client, &inner, &url)
+    })
+  }
+
   #[napi]
-  pub fn list_payments(&mut self) -> Vec<PaymentDetails> {
+  pub fn list_payments(&self) -> Vec<PaymentDetails> {
     let payments = self.inner.list_payments();
     let mut list = Vec::new();
     for item in &payments {
@@ -462,7 +1029,7 @@ impl Node {
   }
 
   #[napi]
-  pub fn list_peers(&mut self) -> Vec<PeerDetails> {
+  pub fn list_peers(&self) -> Vec<PeerDetails> {
     let response_list = self.inner.list_peers();
     let mut list = Vec::new();
     for item in &response_list {
@@ -472,7 +1039,7 @@ impl Node {
   }
 
   #[napi]
-  pub fn list_channels(&mut self) -> Vec<ChannelDetails> {
+  pub fn list_channels(&self) -> Vec<ChannelDetails> {
     let response_list = self.inner.list_channels();
     let mut list = Vec::new();
     for item in &response_list {
@@ -482,24 +1049,40 @@ impl Node {
   }
 
   #[napi]
-  pub fn payment(&mut self, payment_hash: PaymentHash) -> PaymentDetails {
-    let payment = self.inner.payment(&PaymentHash::from_nodejs(payment_hash));
-    PaymentDetails::new(payment.unwrap())
+  pub fn network_graph(&self) -> NetworkGraph {
+    NetworkGraph {
+      inner: self.inner.network_graph(),
+    }
+  }
+
+  #[napi(ts_return_type = "Promise<boolean>")]
+  pub fn export_network_graph(
+    &self,
+    options: ExportNetworkGraphOptions,
+  ) -> AsyncTask<ExportNetworkGraphTask> {
+    AsyncTask::new(ExportNetworkGraphTask::new(
+      self.inner.network_graph(),
+      options,
+    ))
   }
 
   #[napi]
-  pub fn remove_payment(&mut self, payment_hash: PaymentHash) -> Result<bool, Error> {
-    match self
-      .inner
-      .remove_payment(&PaymentHash::from_nodejs(payment_hash))
-    {
+  pub fn payment(&self, payment_id: PaymentId) -> Result<Option<PaymentDetails>, Error> {
+    let payment_id = PaymentId::from_nodejs(payment_id)?;
+    Ok(self.inner.payment(&payment_id).map(PaymentDetails::new))
+  }
+
+  #[napi]
+  pub fn remove_payment(&self, payment_id: PaymentId) -> Result<bool, Error> {
+    let payment_id = PaymentId::from_nodejs(payment_id)?;
+    match self.inner.remove_payment(&payment_id) {
       Ok(()) => Ok(true),
       Err(e) => Err(node_error(e.to_string())),
     }
   }
 
   #[napi]
-  pub fn sign_message(&mut self, msg: Vec<u8>) -> Result<String, Error> {
+  pub fn sign_message(&self, msg: Vec<u8>) -> Result<String, Error> {
     match self.inner.sign_message(&msg) {
       Ok(signed) => Ok(signed),
       Err(e) => Err(node_error(e.to_string())),
@@ -507,7 +1090,7 @@ impl Node {
   }
 
   #[napi]
-  pub fn verify_signature(&mut self, msg: Vec<u8>, sig: String, pkey: &PublicKey) -> bool {
+  pub fn verify_signature(&self, msg: Vec<u8>, sig: String, pkey: &PublicKey) -> bool {
     self
       .inner
       .verify_signature(&msg, &sig, &pkey.inner.to_owned())
@@ -515,13 +1098,15 @@ impl Node {
 
   #[napi]
   pub fn update_channel_config(
-    &mut self,
+    &self,
     channel_id: ChannelId,
     counterparty_node_id: &PublicKey,
     channel_config: &ChannelConfig,
   ) -> Result<bool, Error> {
+    self.ensure_running()?;
+    let user_channel_id = self.user_channel_id(channel_id, counterparty_node_id)?;
     let updated = self.inner.update_channel_config(
-      &ChannelId::from_nodejs(channel_id),
+      &user_channel_id,
       counterparty_node_id.inner.to_owned(),
       ChannelConfig::new(channel_config.to_owned()),
     );
@@ -0,0 +1,699 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use ldk_node::bitcoin::bech32;
+use ldk_node::bitcoin::bech32::FromBase32;
+use ldk_node::bitcoin::hashes::sha256;
+use ldk_node::bitcoin::hashes::Hash;
+use ldk_node::bitcoin::hashes::HashEngine;
+use ldk_node::bitcoin::hashes::Hmac;
+use ldk_node::bitcoin::hashes::HmacEngine;
+use ldk_node::bitcoin::secp256k1::Message;
+use ldk_node::bitcoin::secp256k1::PublicKey;
+use ldk_node::bitcoin::secp256k1::Secp256k1;
+use ldk_node::bitcoin::secp256k1::SecretKey;
+use ldk_node::lightning::ln::channelmanager::PaymentId;
+use ldk_node::lightning_invoice::Bolt11Invoice;
+use ldk_node::lightning_invoice::Bolt11InvoiceDescription;
+use napi::bindgen_prelude::AsyncTask;
+use napi::bindgen_prelude::ToNapiValue;
+use napi::bindgen_prelude::TypeName;
+use napi::Env;
+use napi::JsError;
+use napi::Task;
+use napi_derive::napi;
+use serde_json::Value;
+use url::Url;
+
+use crate::utils::hex_decode;
+use crate::utils::hex_encode;
+use crate::utils::node_error;
+use crate::utils::Error;
+
+const LNURL_AUTH_DERIVATION_MESSAGE: &str = "DO NOT EVER SIGN THIS TEXT WITH YOUR PRIVATE KEYS! IT IS ONLY USED FOR DERIVATION OF LNURL-AUTH HASHING-KEY, DISCLOSING ITS SIGNATURE WILL COMPROMISE YOUR LNURL-AUTH IDENTITY AND MAY LEAD TO LOSS OF FUNDS!";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct PayParams {
+  pub callback: String,
+  pub min_sendable: u64,
+  pub max_sendable: u64,
+  pub metadata: String,
+  pub comment_allowed: u64,
+}
+
+pub struct WithdrawParams {
+  pub callback: String,
+  pub k1: String,
+  pub min_withdrawable: u64,
+  pub max_withdrawable: u64,
+  pub default_description: String,
+}
+
+/// LUD-01 only allows plain `http` for onion services. `allow_http` lifts
+/// that for regtest setups talking to a local LNURL server.
+fn check_scheme(url: &Url, allow_http: bool) -> Result<(), Error> {
+  let onion = url.host_str().is_some_and(|host| host.ends_with(".onion"));
+  match url.scheme() {
+    "https" => Ok(()),
+    "http" if onion || allow_http => Ok(()),
+    scheme => Err(node_error(format!(
+      "LNURL must use https unless it is an onion service, got {}://",
+      scheme
+    ))),
+  }
+}
+
+pub fn decode(lnurl: &str, allow_http: bool) -> Result<Url, Error> {
+  let lnurl = lnurl.trim();
+  let lnurl = lnurl
+    .strip_prefix("lightning:")
+    .or_else(|| lnurl.strip_prefix("LIGHTNING:"))
+    .unwrap_or(lnurl);
+
+  if lnurl.to_lowercase().starts_with("lnurl1") {
+    let (hrp, data, _variant) = bech32::decode(lnurl).map_err(|e| node_error(e.to_string()))?;
+    if hrp != "lnurl" {
+      return Err(node_error(format!("Invalid LNURL prefix: {}", hrp)));
+    }
+    let bytes = Vec::<u8>::from_base32(&data).map_err(|e| node_error(e.to_string()))?;
+    let url = String::from_utf8(bytes).map_err(|e| node_error(e.to_string()))?;
+    let url = Url::parse(&url).map_err(|e| node_error(e.to_string()))?;
+    check_scheme(&url, allow_http)?;
+    return Ok(url);
+  }
+
+  for scheme in ["lnurlp", "lnurlw", "lnurlc", "keyauth"] {
+    if let Some(rest) = lnurl.strip_prefix(&format!("{}://", scheme)) {
+      let host = rest.split(['/', '?', ':']).next().unwrap_or_default();
+      let protocol = if host.ends_with(".onion") {
+        "http"
+      } else {
+        "https"
+      };
+      return Url::parse(&format!("{}://{}", protocol, rest))
+        .map_err(|e| node_error(e.to_string()));
+    }
+  }
+
+  Err(node_error(format!("Not a valid LNURL: {}", lnurl)))
+}
+
+/// Maps a LUD-16 Lightning Address to its LNURL-pay endpoint. The username
+/// is limited to LUD-16's `a-z0-9-_.` and the domain may not carry its own
+/// userinfo, path, query or fragment.
+pub fn lightning_address_url(address: &str) -> Result<Url, Error> {
+  let address = address.trim();
+  let invalid = || node_error(format!("Not a valid Lightning Address: {}", address));
+  let (user, domain) = address.split_once('@').ok_or_else(invalid)?;
+  let user = user.to_lowercase();
+  let valid_user = !user.is_empty()
+    && user
+      .chars()
+      .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-_.".contains(c));
+  let valid_domain = !domain.is_empty()
+    && !domain
+      .chars()
+      .any(|c| c.is_whitespace() || "@/?#\\".contains(c));
+  if !valid_user || !valid_domain {
+    return Err(invalid());
+  }
+  let protocol = if domain.ends_with(".onion") {
+    "http"
+  } else {
+    "https"
+  };
+  Url::parse(&format!(
+    "{}://{}/.well-known/lnurlp/{}",
+    protocol, domain, user
+  ))
+  .map_err(|e| node_error(e.to_string()))
+}
+
+#[napi]
+pub fn decode_lnurl(lnurl: String) -> Result<String, Error> {
+  Ok(decode(&lnurl, false)?.to_string())
+}
+
+/// Blocking LNURL work (HTTP round trips, payments) run on the libuv thread
+/// pool so it never stalls the JS thread.
+pub struct LnurlTask<T> {
+  work: Option<Box<dyn FnOnce() -> Result<T, Error> + Send>>,
+  error: Option<Error>,
+}
+
+impl<T: ToNapiValue + TypeName + Send + 'static> LnurlTask<T> {
+  pub fn spawn(work: impl FnOnce() -> Result<T, Error> + Send + 'static) -> AsyncTask<Self> {
+    AsyncTask::new(LnurlTask {
+      work: Some(Box::new(work)),
+      error: None,
+    })
+  }
+}
+
+impl<T: ToNapiValue + TypeName + Send + 'static> Task for LnurlTask<T> {
+  type Output = T;
+  type JsValue = T;
+
+  fn compute(&mut self) -> napi::Result<T> {
+    let work = match self.work.take() {
+      Some(work) => work,
+      None => return Err(napi::Error::from_reason("LNURL task already ran")),
+    };
+    work().map_err(|e| {
+      let reason = e.reason.clone();
+      self.error = Some(e);
+      napi::Error::from_reason(reason)
+    })
+  }
+
+  fn resolve(&mut self, _env: Env, output: T) -> napi::Result<T> {
+    Ok(output)
+  }
+
+  // `compute` can only hand back a plain `Status` error, so the coded error
+  // is kept aside and rebuilt here to reject the promise with its code.
+  fn reject(&mut self, env: Env, err: napi::Error) -> napi::Result<T> {
+    match self.error.take() {
+      Some(e) => Err(napi::Error::from(JsError::from(e).into_unknown(env))),
+      None => Err(err),
+    }
+  }
+}
+
+pub struct Client {
+  agent: ureq::Agent,
+  allow_http: bool,
+}
+
+impl Client {
+  pub fn new(allow_http: bool) -> Self {
+    Client {
+      agent: ureq::AgentBuilder::new()
+        .timeout_connect(CONNECT_TIMEOUT)
+        .timeout_read(READ_TIMEOUT)
+        .build(),
+      allow_http,
+    }
+  }
+
+  pub fn decode(&self, lnurl: &str) -> Result<Url, Error> {
+    decode(lnurl, self.allow_http)
+  }
+
+  fn get_json(&self, url: &str, query: &[(&str, String)]) -> Result<Value, Error> {
+    check_scheme(
+      &Url::parse(url).map_err(|e| node_error(e.to_string()))?,
+      self.allow_http,
+    )?;
+    let mut request = self.agent.get(url);
+    for (key, value) in query {
+      request = request.query(key, value);
+    }
+    let response: Value = request
+      .call()
+      .map_err(|e| node_error(e.to_string()))?
+      .into_json()
+      .map_err(|e| node_error(e.to_string()))?;
+    if response["status"].as_str() == Some("ERROR") {
+      return Err(node_error(format!(
+        "LNURL service returned an error: {}",
+        response["reason"].as_str().unwrap_or("unknown reason")
+      )));
+    }
+    Ok(response)
+  }
+}
+
+fn string_field(response: &Value, field: &str) -> Result<String, Error> {
+  match response[field].as_str() {
+    Some(value) => Ok(value.to_string()),
+    None => Err(node_error(format!("LNURL response is missing `{}`", field))),
+  }
+}
+
+fn u64_field(response: &Value, field: &str) -> Result<u64, Error> {
+  match response[field].as_u64() {
+    Some(value) => Ok(value),
+    None => Err(node_error(format!("LNURL response is missing `{}`", field))),
+  }
+}
+
+fn expect_tag(response: &Value, tag: &str) -> Result<(), Error> {
+  match response["tag"].as_str() {
+    Some(found) if found == tag => Ok(()),
+    found => Err(node_error(format!(
+      "Expected LNURL tag `{}`, got `{}`",
+      tag,
+      found.unwrap_or_default()
+    ))),
+  }
+}
+
+pub fn fetch_pay_params(client: &Client, url: &str) -> Result<PayParams, Error> {
+  let response = client.get_json(url, &[])?;
+  expect_tag(&response, "payRequest")?;
+  Ok(PayParams {
+    callback: string_field(&response, "callback")?,
+    min_sendable: u64_field(&response, "minSendable")?,
+    max_sendable: u64_field(&response, "maxSendable")?,
+    metadata: string_field(&response, "metadata")?,
+    comment_allowed: response["commentAllowed"].as_u64().unwrap_or(0),
+  })
+}
+
+pub fn fetch_withdraw_params(client: &Client, url: &str) -> Result<WithdrawParams, Error> {
+  let response = client.get_json(url, &[])?;
+  expect_tag(&response, "withdrawRequest")?;
+  Ok(WithdrawParams {
+    callback: string_field(&response, "callback")?,
+    k1: string_field(&response, "k1")?,
+    min_withdrawable: response["minWithdrawable"].as_u64().unwrap_or(0),
+    max_withdrawable: u64_field(&response, "maxWithdrawable")?,
+    default_description: response["defaultDescription"]
+      .as_str()
+      .unwrap_or_default()
+      .to_string(),
+  })
+}
+
+fn invoice_query(
+  params: &PayParams,
+  amount_msat: u64,
+  comment: Option<String>,
+) -> Result<Vec<(&'static str, String)>, Error> {
+  if amount_msat < params.min_sendable || amount_msat > params.max_sendable {
+    return Err(node_error(format!(
+      "Amount {} msat is outside the sendable range {}-{} msat",
+      amount_msat, params.min_sendable, params.max_sendable
+    )));
+  }
+
+  let mut query = vec![("amount", amount_msat.to_string())];
+  if let Some(comment) = comment {
+    if comment.chars().count() as u64 > params.comment_allowed {
+      return Err(node_error(format!(
+        "Comment exceeds the {} characters allowed",
+        params.comment_allowed
+      )));
+    }
+    query.push(("comment", comment));
+  }
+  Ok(query)
+}
+
+fn check_invoice(
+  params: &PayParams,
+  amount_msat: u64,
+  invoice: &Bolt11Invoice,
+) -> Result<(), Error> {
+  if invoice.amount_milli_satoshis() != Some(amount_msat) {
+    return Err(node_error(
+      "Invoice amount does not match the requested amount".to_string(),
+    ));
+  }
+  let metadata_hash = sha256::Hash::hash(params.metadata.as_bytes());
+  match invoice.description() {
+    Bolt11InvoiceDescription::Hash(hash) if hash.0 == metadata_hash => Ok(()),
+    _ => Err(node_error(
+      "Invoice description hash does not match the LNURL metadata".to_string(),
+    )),
+  }
+}
+
+pub fn request_invoice(
+  client: &Client,
+  params: &PayParams,
+  amount_msat: u64,
+  comment: Option<String>,
+) -> Result<Bolt11Invoice, Error> {
+  let query = invoice_query(params, amount_msat, comment)?;
+  let response = client.get_json(&params.callback, &query)?;
+  let invoice = Bolt11Invoice::from_str(&string_field(&response, "pr")?)
+    .map_err(|e| node_error(e.to_string()))?;
+  check_invoice(params, amount_msat, &invoice)?;
+  Ok(invoice)
+}
+
+pub fn pay(
+  client: &Client,
+  node: &ldk_node::Node,
+  url: &str,
+  amount_msat: u64,
+  comment: Option<String>,
+) -> Result<PaymentId, Error> {
+  let params = fetch_pay_params(client, url)?;
+  let invoice = request_invoice(client, &params, amount_msat, comment)?;
+  node
+    .bolt11_payment()
+    .send(&invoice)
+    .map_err(|e| node_error(e.to_string()))
+}
+
+fn withdraw_amount(params: &WithdrawParams, amount_msat: Option<u64>) -> Result<u64, Error> {
+  let amount_msat = amount_msat.unwrap_or(params.max_withdrawable);
+  if amount_msat < params.min_withdrawable || amount_msat > params.max_withdrawable {
+    return Err(node_error(format!(
+      "Amount {} msat is outside the withdrawable range {}-{} msat",
+      amount_msat, params.min_withdrawable, params.max_withdrawable
+    )));
+  }
+  Ok(amount_msat)
+}
+
+pub fn withdraw(
+  client: &Client,
+  node: &ldk_node::Node,
+  url: &str,
+  amount_msat: Option<u64>,
+  description: Option<String>,
+  expiry_secs: u32,
+) -> Result<PaymentId, Error> {
+  let params = fetch_withdraw_params(client, url)?;
+  let amount_msat = withdraw_amount(&params, amount_msat)?;
+  let description = description.unwrap_or(params.default_description);
+  let invoice = node
+    .bolt11_payment()
+    .receive(amount_msat, &description, expiry_secs)
+    .map_err(|e| node_error(e.to_string()))?;
+
+  client.get_json(
+    &params.callback,
+    &[("k1", params.k1), ("pr", invoice.to_string())],
+  )?;
+  Ok(PaymentId(invoice.payment_hash().to_byte_array()))
+}
+
+fn linking_key(node: &ldk_node::Node, domain: &str) -> Result<SecretKey, Error> {
+  let signature = node
+    .sign_message(LNURL_AUTH_DERIVATION_MESSAGE.as_bytes())
+    .map_err(|e| node_error(e.to_string()))?;
+  let hashing_key = sha256::Hash::hash(signature.as_bytes());
+
+  let mut engine = HmacEngine::<sha256::Hash>::new(&hashing_key[..]);
+  engine.input(domain.as_bytes());
+  let linking_key = Hmac::<sha256::Hash>::from_engine(engine);
+  SecretKey::from_slice(&linking_key[..]).map_err(|e| node_error(e.to_string()))
+}
+
+pub fn auth(client: &Client, node: &ldk_node::Node, url: &Url) -> Result<String, Error> {
+  let query = |key: &str| {
+    url
+      .query_pairs()
+      .find(|(k, _)| k == key)
+      .map(|(_, v)| v.into_owned())
+  };
+  if query("tag").as_deref() != Some("login") {
+    return Err(node_error("LNURL is not an LNURL-auth request".to_string()));
+  }
+  let k1 = query("k1").ok_or_else(|| node_error("LNURL-auth is missing `k1`".to_string()))?;
+  let domain = url
+    .host_str()
+    .ok_or_else(|| node_error("LNURL-auth URL has no domain".to_string()))?;
+
+  let k1_bytes = hex_decode(&k1)?;
+  let message = Message::from_slice(&k1_bytes).map_err(|e| node_error(e.to_string()))?;
+  let secp = Secp256k1::new();
+  let secret_key = linking_key(node, domain)?;
+  let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+  let signature = secp.sign_ecdsa(&message, &secret_key);
+
+  client.get_json(
+    url.as_str(),
+    &[
+      ("sig", hex_encode(&signature.serialize_der())),
+      ("key", public_key.to_string()),
+    ],
+  )?;
+  Ok(public_key.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::BufRead;
+  use std::io::BufReader;
+  use std::io::Write;
+  use std::net::TcpListener;
+  use std::thread;
+  use std::thread::JoinHandle;
+
+  use ldk_node::bitcoin::bech32::ToBase32;
+  use ldk_node::bitcoin::bech32::Variant;
+  use ldk_node::lightning::ln::PaymentSecret;
+  use ldk_node::lightning_invoice::Currency;
+  use ldk_node::lightning_invoice::InvoiceBuilder;
+
+  use super::*;
+
+  const METADATA: &str = "[[\"text/plain\",\"coffee\"]]";
+
+  fn encode(hrp: &str, url: &str) -> String {
+    bech32::encode(hrp, url.as_bytes().to_base32(), Variant::Bech32).unwrap()
+  }
+
+  fn pay_params() -> PayParams {
+    PayParams {
+      callback: "https://service.example/lnurlp/callback".to_string(),
+      min_sendable: 1_000,
+      max_sendable: 10_000_000_000,
+      metadata: METADATA.to_string(),
+      comment_allowed: 10,
+    }
+  }
+
+  fn invoice(amount_msat: u64, metadata: &str) -> Bolt11Invoice {
+    let secp = Secp256k1::new();
+    let key = SecretKey::from_slice(&[42; 32]).unwrap();
+    InvoiceBuilder::new(Currency::Bitcoin)
+      .description_hash(sha256::Hash::hash(metadata.as_bytes()))
+      .payment_hash(sha256::Hash::hash(b"preimage"))
+      .payment_secret(PaymentSecret([7; 32]))
+      .duration_since_epoch(Duration::from_secs(1_700_000_000))
+      .min_final_cltv_expiry_delta(144)
+      .amount_milli_satoshis(amount_msat)
+      .build_signed(|hash| secp.sign_ecdsa_recoverable(hash, &key))
+      .unwrap()
+  }
+
+  /// Stand-in LNURL service: answers one connection per response, in order,
+  /// and hands back the request lines it saw.
+  fn serve(responses: Vec<(u16, String)>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+      let mut requests = Vec::new();
+      for (status, body) in responses {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        requests.push(request_line.trim_end().to_string());
+        let mut header = String::new();
+        while reader.read_line(&mut header).unwrap() > 2 {
+          header.clear();
+        }
+        write!(
+          stream,
+          "HTTP/1.1 {} Stand-in\r\n\
+           Content-Type: application/json\r\n\
+           Content-Length: {}\r\n\
+           Connection: close\r\n\r\n{}",
+          status,
+          body.len(),
+          body
+        )
+        .unwrap();
+      }
+      requests
+    });
+    (base_url, handle)
+  }
+
+  #[test]
+  fn decodes_bech32_lnurl() {
+    let url = "https://service.example/lnurl-pay?id=42";
+    let lnurl = encode("lnurl", url);
+    assert_eq!(decode(&lnurl, false).unwrap().as_str(), url);
+    assert_eq!(
+      decode(&format!("lightning:{}", lnurl.to_uppercase()), false)
+        .unwrap()
+        .as_str(),
+      url
+    );
+  }
+
+  #[test]
+  fn rejects_bech32_with_wrong_prefix() {
+    let lnurl = encode("lnurl", "https://service.example");
+    assert!(decode(&lnurl[..lnurl.len() - 1], false).is_err());
+    assert!(decode(&encode("lnbc", "https://service.example"), false).is_err());
+  }
+
+  #[test]
+  fn rejects_clearnet_http_unless_allowed() {
+    let clearnet = encode("lnurl", "http://service.example/lnurl-pay");
+    assert!(decode(&clearnet, false).is_err());
+    assert!(decode(&clearnet, true).is_ok());
+
+    let onion = encode("lnurl", "http://service2ezxcv.onion/lnurl-pay");
+    assert!(decode(&onion, false).is_ok());
+  }
+
+  #[test]
+  fn maps_lud17_schemes() {
+    assert_eq!(
+      decode("lnurlp://service.example/pay?id=1", false)
+        .unwrap()
+        .as_str(),
+      "https://service.example/pay?id=1"
+    );
+    assert_eq!(
+      decode("lnurlw://service2ezxcv.onion/withdraw", false)
+        .unwrap()
+        .as_str(),
+      "http://service2ezxcv.onion/withdraw"
+    );
+    assert!(decode("https://service.example", false).is_err());
+  }
+
+  #[test]
+  fn maps_lightning_addresses() {
+    assert_eq!(
+      lightning_address_url("Alice@service.example")
+        .unwrap()
+        .as_str(),
+      "https://service.example/.well-known/lnurlp/alice"
+    );
+    assert_eq!(
+      lightning_address_url("bob@service2ezxcv.onion")
+        .unwrap()
+        .as_str(),
+      "http://service2ezxcv.onion/.well-known/lnurlp/bob"
+    );
+    assert!(lightning_address_url("@service.example").is_err());
+    assert!(lightning_address_url("alice").is_err());
+    assert!(lightning_address_url("alice@").is_err());
+  }
+
+  #[test]
+  fn rejects_malformed_lightning_addresses() {
+    assert!(lightning_address_url("alice?x=1@service.example").is_err());
+    assert!(lightning_address_url("a@b@service.example").is_err());
+    assert!(lightning_address_url("al ice@service.example").is_err());
+    assert!(lightning_address_url("alice@service.example/path").is_err());
+    assert!(lightning_address_url("alice@service.example#frag").is_err());
+    assert!(lightning_address_url("alice@service.example?x=1").is_err());
+    assert_eq!(
+      lightning_address_url("a-l_i.ce9@service.example")
+        .unwrap()
+        .as_str(),
+      "https://service.example/.well-known/lnurlp/a-l_i.ce9"
+    );
+  }
+
+  #[test]
+  fn rejects_amount_outside_sendable_range() {
+    let params = pay_params();
+    assert!(invoice_query(&params, 999, None).is_err());
+    assert!(invoice_query(&params, 10_000_000_001, None).is_err());
+    assert_eq!(
+      invoice_query(&params, 10_000_000_000, None).unwrap(),
+      vec![("amount", "10000000000".to_string())]
+    );
+  }
+
+  #[test]
+  fn rejects_comment_over_allowed_length() {
+    let params = pay_params();
+    assert!(invoice_query(&params, 5_000, Some("x".repeat(11))).is_err());
+    assert!(invoice_query(&params, 5_000, Some("é".repeat(10))).is_ok());
+  }
+
+  #[test]
+  fn checks_invoice_against_request() {
+    let params = pay_params();
+    assert!(check_invoice(&params, 5_000, &invoice(5_000, METADATA)).is_ok());
+    assert!(check_invoice(&params, 5_000, &invoice(6_000, METADATA)).is_err());
+    assert!(check_invoice(&params, 5_000, &invoice(5_000, "[]")).is_err());
+  }
+
+  #[test]
+  fn rejects_withdraw_amount_outside_range() {
+    let params = WithdrawParams {
+      callback: "https://service.example/withdraw".to_string(),
+      k1: "00".to_string(),
+      min_withdrawable: 1_000,
+      max_withdrawable: 50_000,
+      default_description: String::new(),
+    };
+    assert_eq!(withdraw_amount(&params, None).unwrap(), 50_000);
+    assert!(withdraw_amount(&params, Some(999)).is_err());
+    assert!(withdraw_amount(&params, Some(50_001)).is_err());
+  }
+
+  #[test]
+  fn pays_through_stand_in_service() {
+    let (base_url, server) = serve(vec![
+      (
+        200,
+        serde_json::json!({
+          "tag": "payRequest",
+          "callback": "CALLBACK",
+          "minSendable": 1_000,
+          "maxSendable": 10_000_000,
+          "metadata": METADATA,
+          "commentAllowed": 5,
+        })
+        .to_string(),
+      ),
+      (
+        200,
+        serde_json::json!({ "pr": invoice(5_000, METADATA).to_string() }).to_string(),
+      ),
+    ]);
+    let client = Client::new(true);
+
+    let mut params = fetch_pay_params(&client, &format!("{}/lnurlp/alice", base_url)).unwrap();
+    assert_eq!(params.callback, "CALLBACK");
+    assert_eq!(params.min_sendable, 1_000);
+    assert_eq!(params.max_sendable, 10_000_000);
+    assert_eq!(params.metadata, METADATA);
+    assert_eq!(params.comment_allowed, 5);
+
+    params.callback = format!("{}/lnurlp/callback", base_url);
+    let invoice = request_invoice(&client, &params, 5_000, Some("hi".to_string())).unwrap();
+    assert_eq!(invoice.amount_milli_satoshis(), Some(5_000));
+
+    assert_eq!(
+      server.join().unwrap(),
+      vec![
+        "GET /lnurlp/alice HTTP/1.1".to_string(),
+        "GET /lnurlp/callback?amount=5000&comment=hi HTTP/1.1".to_string(),
+      ]
+    );
+  }
+
+  #[test]
+  fn reports_service_errors() {
+    let (base_url, server) = serve(vec![
+      (
+        200,
+        serde_json::json!({ "status": "ERROR", "reason": "Unknown user" }).to_string(),
+      ),
+      (500, "{}".to_string()),
+    ]);
+    let client = Client::new(true);
+    let url = format!("{}/lnurlp/alice", base_url);
+
+    let error = fetch_pay_params(&client, &url).err().unwrap();
+    assert_eq!(
+      error.reason,
+      "LNURL service returned an error: Unknown user"
+    );
+    assert!(fetch_pay_params(&client, &url).is_err());
+    server.join().unwrap();
+
+    assert!(fetch_pay_params(&Client::new(false), &url).is_err());
+  }
+}
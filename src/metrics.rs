@@ -0,0 +1,315 @@
+use std::fmt::Write as _;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use ldk_node::payment::PaymentDirection;
+use ldk_node::payment::PaymentStatus;
+
+#[derive(Default)]
+pub struct SyncStats {
+  pub successes: AtomicU64,
+  pub failures: AtomicU64,
+  pub last_duration_ms: AtomicU64,
+  pub total_duration_ms: AtomicU64,
+}
+
+impl SyncStats {
+  pub fn record(&self, duration: Duration, succeeded: bool) {
+    let millis = duration.as_millis() as u64;
+    if succeeded {
+      self.successes.fetch_add(1, Ordering::Relaxed);
+    } else {
+      self.failures.fetch_add(1, Ordering::Relaxed);
+    }
+    self.last_duration_ms.store(millis, Ordering::Relaxed);
+    self.total_duration_ms.fetch_add(millis, Ordering::Relaxed);
+  }
+}
+
+pub struct MetricsServer {
+  shutdown: Arc<AtomicBool>,
+  handle: Option<JoinHandle<()>>,
+}
+
+impl MetricsServer {
+  pub fn start(
+    node: Arc<ldk_node::Node>,
+    sync_stats: Arc<SyncStats>,
+    port: u16,
+  ) -> std::io::Result<Self> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    listener.set_nonblocking(true)?;
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let stop = Arc::clone(&shutdown);
+    let handle = thread::spawn(move || {
+      while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+          Ok((stream, _)) => {
+            let _ = respond(stream, &node, &sync_stats);
+          }
+          Err(_) => thread::sleep(Duration::from_millis(100)),
+        }
+      }
+    });
+    Ok(MetricsServer {
+      shutdown,
+      handle: Some(handle),
+    })
+  }
+
+  pub fn stop(&mut self) {
+    self.shutdown.store(true, Ordering::Relaxed);
+    if let Some(handle) = self.handle.take() {
+      let _ = handle.join();
+    }
+  }
+}
+
+impl Drop for MetricsServer {
+  fn drop(&mut self) {
+    self.stop();
+  }
+}
+
+fn respond(
+  stream: TcpStream,
+  node: &ldk_node::Node,
+  sync_stats: &SyncStats,
+) -> std::io::Result<()> {
+  stream.set_nonblocking(false)?;
+  stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+  let mut request_line = String::new();
+  BufReader::new(&stream).read_line(&mut request_line)?;
+  let mut stream = stream;
+
+  let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+  if path != "/metrics" {
+    return stream
+      .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+  }
+
+  let body = render(node, sync_stats);
+  write!(
+    stream,
+    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+    body.len(),
+    body
+  )
+}
+
+fn metric(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(&str, u64)]) {
+  let _ = writeln!(out, "# HELP {} {}", name, help);
+  let _ = writeln!(out, "# TYPE {} {}", name, kind);
+  for (labels, value) in samples {
+    if labels.is_empty() {
+      let _ = writeln!(out, "{} {}", name, value);
+    } else {
+      let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+    }
+  }
+}
+
+pub fn render(node: &ldk_node::Node, sync_stats: &SyncStats) -> String {
+  let mut out = String::new();
+
+  let balances = node.list_balances();
+  metric(
+    &mut out,
+    "ldk_node_onchain_balance_sats",
+    "gauge",
+    "On-chain wallet balance in satoshis.",
+    &[
+      ("kind=\"total\"", balances.total_onchain_balance_sats),
+      (
+        "kind=\"spendable\"",
+        balances.spendable_onchain_balance_sats,
+      ),
+    ],
+  );
+  metric(
+    &mut out,
+    "ldk_node_lightning_balance_sats",
+    "gauge",
+    "Total Lightning balance in satoshis, including funds claimable from closing channels.",
+    &[("", balances.total_lightning_balance_sats)],
+  );
+  let pending_sweep_sats: u64 = balances
+    .pending_balances_from_channel_closures
+    .iter()
+    .map(|balance| match balance {
+      ldk_node::PendingSweepBalance::PendingBroadcast {
+        amount_satoshis, ..
+      }
+      | ldk_node::PendingSweepBalance::BroadcastAwaitingConfirmation {
+        amount_satoshis, ..
+      }
+      | ldk_node::PendingSweepBalance::AwaitingThresholdConfirmations {
+        amount_satoshis, ..
+      } => *amount_satoshis,
+    })
+    .sum();
+  metric(
+    &mut out,
+    "ldk_node_pending_sweep_balance_sats",
+    "gauge",
+    "Funds from closed channels not yet swept back into the on-chain wallet.",
+    &[("", pending_sweep_sats)],
+  );
+
+  let channels = node.list_channels();
+  let usable = channels.iter().filter(|c| c.is_usable).count() as u64;
+  let ready = channels.iter().filter(|c| c.is_channel_ready).count() as u64;
+  metric(
+    &mut out,
+    "ldk_node_channels",
+    "gauge",
+    "Number of channels by state.",
+    &[
+      ("state=\"usable\"", usable),
+      ("state=\"ready_not_usable\"", ready.saturating_sub(usable)),
+      (
+        "state=\"pending\"",
+        (channels.len() as u64).saturating_sub(ready),
+      ),
+    ],
+  );
+
+  let peers = node.list_peers();
+  metric(
+    &mut out,
+    "ldk_node_peers",
+    "gauge",
+    "Number of known peers by connection state.",
+    &[
+      (
+        "state=\"connected\"",
+        peers.iter().filter(|p| p.is_connected).count() as u64,
+      ),
+      (
+        "state=\"disconnected\"",
+        peers.iter().filter(|p| !p.is_connected).count() as u64,
+      ),
+    ],
+  );
+
+  let payments = node.list_payments();
+  let count = |direction: PaymentDirection, status: PaymentStatus| {
+    payments
+      .iter()
+      .filter(|p| p.direction == direction && p.status == status)
+      .count() as u64
+  };
+  metric(
+    &mut out,
+    "ldk_node_payments",
+    "gauge",
+    "Number of stored payments by direction and status.",
+    &[
+      (
+        "direction=\"outbound\",status=\"succeeded\"",
+        count(PaymentDirection::Outbound, PaymentStatus::Succeeded),
+      ),
+      (
+        "direction=\"outbound\",status=\"failed\"",
+        count(PaymentDirection::Outbound, PaymentStatus::Failed),
+      ),
+      (
+        "direction=\"outbound\",status=\"pending\"",
+        count(PaymentDirection::Outbound, PaymentStatus::Pending),
+      ),
+      (
+        "direction=\"inbound\",status=\"succeeded\"",
+        count(PaymentDirection::Inbound, PaymentStatus::Succeeded),
+      ),
+      (
+        "direction=\"inbound\",status=\"failed\"",
+        count(PaymentDirection::Inbound, PaymentStatus::Failed),
+      ),
+      (
+        "direction=\"inbound\",status=\"pending\"",
+        count(PaymentDirection::Inbound, PaymentStatus::Pending),
+      ),
+    ],
+  );
+
+  metric(
+    &mut out,
+    "ldk_node_wallet_syncs_total",
+    "counter",
+    "Number of explicit syncWallets calls by result.",
+    &[
+      (
+        "result=\"success\"",
+        sync_stats.successes.load(Ordering::Relaxed),
+      ),
+      (
+        "result=\"failure\"",
+        sync_stats.failures.load(Ordering::Relaxed),
+      ),
+    ],
+  );
+  metric(
+    &mut out,
+    "ldk_node_wallet_sync_duration_milliseconds_total",
+    "counter",
+    "Total time spent in explicit syncWallets calls.",
+    &[("", sync_stats.total_duration_ms.load(Ordering::Relaxed))],
+  );
+  metric(
+    &mut out,
+    "ldk_node_wallet_sync_last_duration_milliseconds",
+    "gauge",
+    "Duration of the most recent explicit syncWallets call.",
+    &[("", sync_stats.last_duration_ms.load(Ordering::Relaxed))],
+  );
+
+  let status = node.status();
+  let timestamps = [
+    (
+      "kind=\"lightning_wallet\"",
+      status.latest_wallet_sync_timestamp,
+    ),
+    (
+      "kind=\"onchain_wallet\"",
+      status.latest_onchain_wallet_sync_timestamp,
+    ),
+    (
+      "kind=\"fee_rate_cache\"",
+      status.latest_fee_rate_cache_update_timestamp,
+    ),
+    (
+      "kind=\"rgs_snapshot\"",
+      status.latest_rgs_snapshot_timestamp,
+    ),
+  ];
+  let samples: Vec<(&str, u64)> = timestamps
+    .iter()
+    .filter_map(|(labels, timestamp)| timestamp.map(|t| (*labels, t)))
+    .collect();
+  metric(
+    &mut out,
+    "ldk_node_last_sync_timestamp_seconds",
+    "gauge",
+    "UNIX time of the latest successful background sync by kind.",
+    &samples,
+  );
+  metric(
+    &mut out,
+    "ldk_node_best_block_height",
+    "gauge",
+    "Height of the best block the node has synced to.",
+    &[("", status.current_best_block.height as u64)],
+  );
+
+  out
+}
@@ -3,7 +3,6 @@ use std::sync::Arc;
 
 use napi::bindgen_prelude::FromNapiValue;
 use napi::bindgen_prelude::ToNapiValue;
-use napi::Error;
 use napi_derive::napi;
 
 use crate::PublicKey;
@@ -77,14 +76,14 @@ pub struct ChannelId {
 }
 
 impl ChannelId {
-  pub fn from_ldk_node(value: ldk_node::ChannelId) -> Self {
+  pub fn from_ldk_node(value: ldk_node::lightning::ln::ChannelId) -> Self {
     ChannelId {
       channel_id_hex: value.0.to_vec(),
     }
   }
 
-  pub fn from_nodejs(channel_id: ChannelId) -> ldk_node::ChannelId {
-    ldk_node::ChannelId(channel_id.channel_id_hex.to_owned().try_into().unwrap())
+  pub fn from_nodejs(channel_id: ChannelId) -> ldk_node::lightning::ln::ChannelId {
+    ldk_node::lightning::ln::ChannelId(channel_id.channel_id_hex.to_owned().try_into().unwrap())
   }
 }
 
@@ -128,7 +127,6 @@ pub struct ChannelDetails {
   pub unspendable_punishment_reserve: Option<u32>,
   pub user_channel_id: UserChannelId,
   pub feerate_sat_per_1000_weight: u32,
-  pub balance_msat: u32,
   pub outbound_capacity_msat: u32,
   pub inbound_capacity_msat: u32,
   pub confirmations_required: Option<u32>,
@@ -159,7 +157,6 @@ impl ChannelDetails {
       unspendable_punishment_reserve: punishment_value,
       user_channel_id: UserChannelId::from(channel.user_channel_id),
       feerate_sat_per_1000_weight: channel.feerate_sat_per_1000_weight,
-      balance_msat: channel.balance_msat as u32,
       outbound_capacity_msat: channel.outbound_capacity_msat as u32,
       inbound_capacity_msat: channel.inbound_capacity_msat as u32,
       confirmations_required: channel.confirmations_required,
@@ -173,8 +170,76 @@ impl ChannelDetails {
   }
 }
 
-pub fn node_error(e: String) -> napi::Error {
-  Error::new(napi::Status::GenericFailure, e)
+pub fn node_error(e: String) -> Error {
+  Error::new(ErrorCode::GenericFailure, e)
+}
+
+pub fn amount_from_nodejs(amount: i64) -> Result<u64, Error> {
+  match u64::try_from(amount) {
+    Ok(amount) => Ok(amount),
+    Err(_) => Err(node_error(format!(
+      "Amount must not be negative: {}",
+      amount
+    ))),
+  }
+}
+
+pub fn hex_encode(bytes: &[u8]) -> String {
+  bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn hex_decode(hex: &str) -> Result<Vec<u8>, Error> {
+  if hex.len() % 2 != 0 || !hex.is_ascii() {
+    return Err(node_error(format!("Invalid hex string: {}", hex)));
+  }
+  (0..hex.len())
+    .step_by(2)
+    .map(|i| {
+      u8::from_str_radix(&hex[i..i + 2], 16)
+        .map_err(|_| node_error(format!("Invalid hex string: {}", hex)))
+    })
+    .collect()
+}
+
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeLifecycleState {
+  Built,
+  Running,
+  Stopped,
+}
+
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleError {
+  AlreadyRunning,
+  NotRunning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+  GenericFailure,
+  Lifecycle(LifecycleError),
+}
+
+impl AsRef<str> for ErrorCode {
+  fn as_ref(&self) -> &str {
+    match self {
+      ErrorCode::GenericFailure => "GenericFailure",
+      ErrorCode::Lifecycle(LifecycleError::AlreadyRunning) => "AlreadyRunning",
+      ErrorCode::Lifecycle(LifecycleError::NotRunning) => "NotRunning",
+    }
+  }
+}
+
+pub type Error = napi::Error<ErrorCode>;
+
+pub fn lifecycle_error(e: LifecycleError) -> Error {
+  let reason = match e {
+    LifecycleError::AlreadyRunning => "Node is already running",
+    LifecycleError::NotRunning => "Node is not running",
+  };
+  Error::new(ErrorCode::Lifecycle(e), reason.to_string())
 }
 
 #[napi(object)]
@@ -205,47 +270,135 @@ impl PaymentSecret {
   }
 }
 
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaymentId {
+  pub field0: Vec<u8>,
+}
+
+impl PaymentId {
+  pub fn from_ldk_node(id: ldk_node::lightning::ln::channelmanager::PaymentId) -> PaymentId {
+    PaymentId {
+      field0: id.0.to_vec(),
+    }
+  }
+
+  pub fn from_nodejs(
+    id: PaymentId,
+  ) -> Result<ldk_node::lightning::ln::channelmanager::PaymentId, Error> {
+    let bytes: Result<[u8; 32], _> = id.field0.try_into();
+    match bytes {
+      Ok(bytes) => Ok(ldk_node::lightning::ln::channelmanager::PaymentId(bytes)),
+      Err(bytes) => Err(node_error(format!(
+        "Invalid payment id: expected 32 bytes, got {}",
+        bytes.len()
+      ))),
+    }
+  }
+}
+
+#[napi(string_enum)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum PaymentKind {
+  Onchain,
+  Bolt11,
+  Bolt11Jit,
+  Bolt12Offer,
+  Bolt12Refund,
+  Spontaneous,
+}
+
 #[napi(object)]
 #[derive(Debug)]
 pub struct PaymentDetails {
-  pub hash: PaymentHash,
+  pub id: PaymentId,
+  pub kind: PaymentKind,
+  pub hash: Option<PaymentHash>,
   pub preimage: Option<PaymentPreimage>,
   pub secret: Option<PaymentSecret>,
-  pub amount_msat: Option<u32>,
+  pub amount_msat: Option<u64>,
   pub direction: PaymentDirection,
   pub status: PaymentStatus,
+  pub lsp_fee_limits: Option<LspFeeLimits>,
+  pub offer_id: Option<OfferId>,
 }
 
 impl PaymentDetails {
-  pub fn new(payment: ldk_node::PaymentDetails) -> Self {
-    let amount_value;
-    if payment.amount_msat.is_none() {
-      amount_value = None;
-    } else {
-      amount_value = Some(payment.amount_msat.unwrap() as u32);
+  pub fn new(payment: ldk_node::payment::PaymentDetails) -> Self {
+    let mut details = PaymentDetails {
+      id: PaymentId::from_ldk_node(payment.id),
+      kind: PaymentKind::Onchain,
+      hash: None,
+      preimage: None,
+      secret: None,
+      amount_msat: payment.amount_msat,
+      direction: payment.direction.into(),
+      status: payment.status.into(),
+      lsp_fee_limits: None,
+      offer_id: None,
     };
+    let (hash, preimage, secret) = match payment.kind {
+      ldk_node::payment::PaymentKind::Onchain => return details,
+      ldk_node::payment::PaymentKind::Bolt11 {
+        hash,
+        preimage,
+        secret,
+      } => {
+        details.kind = PaymentKind::Bolt11;
+        (Some(hash), preimage, secret)
+      }
+      ldk_node::payment::PaymentKind::Bolt11Jit {
+        hash,
+        preimage,
+        secret,
+        lsp_fee_limits,
+      } => {
+        details.kind = PaymentKind::Bolt11Jit;
+        details.lsp_fee_limits = Some(LspFeeLimits::from(lsp_fee_limits));
+        (Some(hash), preimage, secret)
+      }
+      ldk_node::payment::PaymentKind::Bolt12Offer {
+        hash,
+        preimage,
+        secret,
+        offer_id,
+      } => {
+        details.kind = PaymentKind::Bolt12Offer;
+        details.offer_id = Some(OfferId::from_ldk_node(offer_id));
+        (hash, preimage, secret)
+      }
+      ldk_node::payment::PaymentKind::Bolt12Refund {
+        hash,
+        preimage,
+        secret,
+      } => {
+        details.kind = PaymentKind::Bolt12Refund;
+        (hash, preimage, secret)
+      }
+      ldk_node::payment::PaymentKind::Spontaneous { hash, preimage } => {
+        details.kind = PaymentKind::Spontaneous;
+        (Some(hash), preimage, None)
+      }
+    };
+    details.hash = hash.map(PaymentHash::from_ldk_node);
+    details.preimage = preimage.map(PaymentPreimage::from);
+    details.secret = secret.map(PaymentSecret::from);
+    details
+  }
+}
 
-    let pre_image;
-    if payment.preimage.is_none() {
-      pre_image = None;
-    } else {
-      pre_image = Some(PaymentPreimage::from(payment.preimage.unwrap()));
-    }
-
-    let secret;
-    if payment.secret.is_none() {
-      secret = None;
-    } else {
-      secret = Some(PaymentSecret::from(payment.secret.unwrap()));
-    }
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct LspFeeLimits {
+  pub max_total_opening_fee_msat: Option<u64>,
+  pub max_proportional_opening_fee_ppm_msat: Option<u64>,
+}
 
-    PaymentDetails {
-      hash: PaymentHash::from_ldk_node(payment.hash),
-      preimage: pre_image,
-      secret: secret,
-      amount_msat: amount_value,
-      direction: payment.direction.into(),
-      status: payment.status.into(),
+impl LspFeeLimits {
+  pub fn from(value: ldk_node::payment::LSPFeeLimits) -> Self {
+    LspFeeLimits {
+      max_total_opening_fee_msat: value.max_total_opening_fee_msat,
+      max_proportional_opening_fee_ppm_msat: value.max_proportional_opening_fee_ppm_msat,
     }
   }
 }
@@ -258,12 +411,12 @@ pub enum PaymentStatus {
   Failed,
 }
 
-impl From<ldk_node::PaymentStatus> for PaymentStatus {
-  fn from(value: ldk_node::PaymentStatus) -> Self {
+impl From<ldk_node::payment::PaymentStatus> for PaymentStatus {
+  fn from(value: ldk_node::payment::PaymentStatus) -> Self {
     match value {
-      ldk_node::PaymentStatus::Pending => PaymentStatus::Pending,
-      ldk_node::PaymentStatus::Succeeded => PaymentStatus::Succeeded,
-      ldk_node::PaymentStatus::Failed => PaymentStatus::Failed,
+      ldk_node::payment::PaymentStatus::Pending => PaymentStatus::Pending,
+      ldk_node::payment::PaymentStatus::Succeeded => PaymentStatus::Succeeded,
+      ldk_node::payment::PaymentStatus::Failed => PaymentStatus::Failed,
     }
   }
 }
@@ -275,20 +428,20 @@ pub enum PaymentDirection {
   Outbound,
 }
 
-impl From<ldk_node::PaymentDirection> for PaymentDirection {
-  fn from(value: ldk_node::PaymentDirection) -> Self {
+impl From<ldk_node::payment::PaymentDirection> for PaymentDirection {
+  fn from(value: ldk_node::payment::PaymentDirection) -> Self {
     match value {
-      ldk_node::PaymentDirection::Inbound => PaymentDirection::Inbound,
-      ldk_node::PaymentDirection::Outbound => PaymentDirection::Outbound,
+      ldk_node::payment::PaymentDirection::Inbound => PaymentDirection::Inbound,
+      ldk_node::payment::PaymentDirection::Outbound => PaymentDirection::Outbound,
     }
   }
 }
 
-impl From<PaymentDirection> for ldk_node::PaymentDirection {
+impl From<PaymentDirection> for ldk_node::payment::PaymentDirection {
   fn from(value: PaymentDirection) -> Self {
     match value {
-      PaymentDirection::Inbound => ldk_node::PaymentDirection::Inbound,
-      PaymentDirection::Outbound => ldk_node::PaymentDirection::Outbound,
+      PaymentDirection::Inbound => ldk_node::payment::PaymentDirection::Inbound,
+      PaymentDirection::Outbound => ldk_node::payment::PaymentDirection::Outbound,
     }
   }
 }
@@ -324,8 +477,18 @@ impl Address {
     }
   }
 
-  pub fn from_nodejs(address: &Address) -> ldk_node::bitcoin::Address {
-    ldk_node::bitcoin::Address::from_str(&address.address_hex).unwrap()
+  pub fn from_nodejs(
+    address: &Address,
+    network: ldk_node::bitcoin::Network,
+  ) -> Result<ldk_node::bitcoin::Address, Error> {
+    let address = match ldk_node::bitcoin::Address::from_str(&address.address_hex) {
+      Ok(address) => address,
+      Err(e) => return Err(node_error(e.to_string())),
+    };
+    match address.require_network(network) {
+      Ok(address) => Ok(address),
+      Err(e) => Err(node_error(e.to_string())),
+    }
   }
 }
 
@@ -336,7 +499,7 @@ pub struct Txid {
 }
 
 impl Txid {
-  pub fn from_ldk_node(txid: ldk_node::bitcoin::hash_types::Txid) -> Txid {
+  pub fn from_ldk_node(txid: ldk_node::bitcoin::Txid) -> Txid {
     Txid {
       feild0: txid.to_string(),
     }
@@ -356,7 +519,7 @@ pub struct ChannelConfig {
 
 #[napi]
 impl ChannelConfig {
-  pub fn new(channel_config: ChannelConfig) -> Arc<ldk_node::ChannelConfig> {
+  pub fn new(channel_config: ChannelConfig) -> Arc<ldk_node::config::ChannelConfig> {
     let config = ldk_node::lightning::util::config::ChannelConfig {
       forwarding_fee_proportional_millionths: channel_config.forwarding_fee_proportional_millionths,
       forwarding_fee_base_msat: channel_config.forwarding_fee_base_msat,
@@ -369,7 +532,7 @@ impl ChannelConfig {
         as u64,
       accept_underpaying_htlcs: channel_config.accept_underpaying_htlcs,
     };
-    Arc::new(ldk_node::ChannelConfig::try_from(config).unwrap())
+    Arc::new(ldk_node::config::ChannelConfig::from(config))
   }
 }
 
@@ -410,3 +573,480 @@ pub struct ChannelClosed {
 pub fn get_event(value: ldk_node::Event) -> String {
   format!("Parse each event {:#?}", value)
 }
+
+#[napi(object)]
+#[derive(Debug)]
+pub struct BalanceDetails {
+  pub total_onchain_balance_sats: u64,
+  pub spendable_onchain_balance_sats: u64,
+  pub total_lightning_balance_sats: u64,
+  pub lightning_balances: Vec<LightningBalance>,
+  pub pending_balances_from_channel_closures: Vec<PendingSweepBalance>,
+}
+
+impl BalanceDetails {
+  pub fn new(balances: ldk_node::BalanceDetails) -> Self {
+    BalanceDetails {
+      total_onchain_balance_sats: balances.total_onchain_balance_sats,
+      spendable_onchain_balance_sats: balances.spendable_onchain_balance_sats,
+      total_lightning_balance_sats: balances.total_lightning_balance_sats,
+      lightning_balances: balances
+        .lightning_balances
+        .into_iter()
+        .map(LightningBalance::new)
+        .collect(),
+      pending_balances_from_channel_closures: balances
+        .pending_balances_from_channel_closures
+        .into_iter()
+        .map(PendingSweepBalance::new)
+        .collect(),
+    }
+  }
+}
+
+#[napi(string_enum)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum LightningBalanceKind {
+  ClaimableOnChannelClose,
+  ClaimableAwaitingConfirmations,
+  ContentiousClaimable,
+  MaybeTimeoutClaimableHTLC,
+  MaybePreimageClaimableHTLC,
+  CounterpartyRevokedOutputClaimable,
+}
+
+#[napi(object)]
+#[derive(Debug)]
+pub struct LightningBalance {
+  pub kind: LightningBalanceKind,
+  pub channel_id: ChannelId,
+  pub counterparty_node_id: String,
+  pub amount_satoshis: u64,
+  pub confirmation_height: Option<u32>,
+  pub timeout_height: Option<u32>,
+  pub claimable_height: Option<u32>,
+  pub expiry_height: Option<u32>,
+  pub payment_hash: Option<PaymentHash>,
+  pub payment_preimage: Option<PaymentPreimage>,
+}
+
+impl LightningBalance {
+  fn with_kind(
+    kind: LightningBalanceKind,
+    channel_id: ldk_node::lightning::ln::ChannelId,
+    counterparty_node_id: ldk_node::bitcoin::secp256k1::PublicKey,
+    amount_satoshis: u64,
+  ) -> Self {
+    LightningBalance {
+      kind,
+      channel_id: ChannelId::from_ldk_node(channel_id),
+      counterparty_node_id: counterparty_node_id.to_string(),
+      amount_satoshis,
+      confirmation_height: None,
+      timeout_height: None,
+      claimable_height: None,
+      expiry_height: None,
+      payment_hash: None,
+      payment_preimage: None,
+    }
+  }
+
+  pub fn new(balance: ldk_node::LightningBalance) -> Self {
+    match balance {
+      ldk_node::LightningBalance::ClaimableOnChannelClose {
+        channel_id,
+        counterparty_node_id,
+        amount_satoshis,
+      } => LightningBalance::with_kind(
+        LightningBalanceKind::ClaimableOnChannelClose,
+        channel_id,
+        counterparty_node_id,
+        amount_satoshis,
+      ),
+      ldk_node::LightningBalance::ClaimableAwaitingConfirmations {
+        channel_id,
+        counterparty_node_id,
+        amount_satoshis,
+        confirmation_height,
+      } => LightningBalance {
+        confirmation_height: Some(confirmation_height),
+        ..LightningBalance::with_kind(
+          LightningBalanceKind::ClaimableAwaitingConfirmations,
+          channel_id,
+          counterparty_node_id,
+          amount_satoshis,
+        )
+      },
+      ldk_node::LightningBalance::ContentiousClaimable {
+        channel_id,
+        counterparty_node_id,
+        amount_satoshis,
+        timeout_height,
+        payment_hash,
+        payment_preimage,
+      } => LightningBalance {
+        timeout_height: Some(timeout_height),
+        payment_hash: Some(PaymentHash::from_ldk_node(payment_hash)),
+        payment_preimage: Some(PaymentPreimage::from(payment_preimage)),
+        ..LightningBalance::with_kind(
+          LightningBalanceKind::ContentiousClaimable,
+          channel_id,
+          counterparty_node_id,
+          amount_satoshis,
+        )
+      },
+      ldk_node::LightningBalance::MaybeTimeoutClaimableHTLC {
+        channel_id,
+        counterparty_node_id,
+        amount_satoshis,
+        claimable_height,
+        payment_hash,
+      } => LightningBalance {
+        claimable_height: Some(claimable_height),
+        payment_hash: Some(PaymentHash::from_ldk_node(payment_hash)),
+        ..LightningBalance::with_kind(
+          LightningBalanceKind::MaybeTimeoutClaimableHTLC,
+          channel_id,
+          counterparty_node_id,
+          amount_satoshis,
+        )
+      },
+      ldk_node::LightningBalance::MaybePreimageClaimableHTLC {
+        channel_id,
+        counterparty_node_id,
+        amount_satoshis,
+        expiry_height,
+        payment_hash,
+      } => LightningBalance {
+        expiry_height: Some(expiry_height),
+        payment_hash: Some(PaymentHash::from_ldk_node(payment_hash)),
+        ..LightningBalance::with_kind(
+          LightningBalanceKind::MaybePreimageClaimableHTLC,
+          channel_id,
+          counterparty_node_id,
+          amount_satoshis,
+        )
+      },
+      ldk_node::LightningBalance::CounterpartyRevokedOutputClaimable {
+        channel_id,
+        counterparty_node_id,
+        amount_satoshis,
+      } => LightningBalance::with_kind(
+        LightningBalanceKind::CounterpartyRevokedOutputClaimable,
+        channel_id,
+        counterparty_node_id,
+        amount_satoshis,
+      ),
+    }
+  }
+}
+
+#[napi(string_enum)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum PendingSweepBalanceKind {
+  PendingBroadcast,
+  BroadcastAwaitingConfirmation,
+  AwaitingThresholdConfirmations,
+}
+
+#[napi(object)]
+#[derive(Debug)]
+pub struct PendingSweepBalance {
+  pub kind: PendingSweepBalanceKind,
+  pub channel_id: Option<ChannelId>,
+  pub amount_satoshis: u64,
+  pub latest_broadcast_height: Option<u32>,
+  pub latest_spending_txid: Option<Txid>,
+  pub confirmation_hash: Option<String>,
+  pub confirmation_height: Option<u32>,
+}
+
+impl PendingSweepBalance {
+  pub fn new(balance: ldk_node::PendingSweepBalance) -> Self {
+    match balance {
+      ldk_node::PendingSweepBalance::PendingBroadcast {
+        channel_id,
+        amount_satoshis,
+      } => PendingSweepBalance {
+        kind: PendingSweepBalanceKind::PendingBroadcast,
+        channel_id: channel_id.map(ChannelId::from_ldk_node),
+        amount_satoshis,
+        latest_broadcast_height: None,
+        latest_spending_txid: None,
+        confirmation_hash: None,
+        confirmation_height: None,
+      },
+      ldk_node::PendingSweepBalance::BroadcastAwaitingConfirmation {
+        channel_id,
+        latest_broadcast_height,
+        latest_spending_txid,
+        amount_satoshis,
+      } => PendingSweepBalance {
+        kind: PendingSweepBalanceKind::BroadcastAwaitingConfirmation,
+        channel_id: channel_id.map(ChannelId::from_ldk_node),
+        amount_satoshis,
+        latest_broadcast_height: Some(latest_broadcast_height),
+        latest_spending_txid: Some(Txid::from_ldk_node(latest_spending_txid)),
+        confirmation_hash: None,
+        confirmation_height: None,
+      },
+      ldk_node::PendingSweepBalance::AwaitingThresholdConfirmations {
+        channel_id,
+        latest_spending_txid,
+        confirmation_hash,
+        confirmation_height,
+        amount_satoshis,
+      } => PendingSweepBalance {
+        kind: PendingSweepBalanceKind::AwaitingThresholdConfirmations,
+        channel_id: channel_id.map(ChannelId::from_ldk_node),
+        amount_satoshis,
+        latest_broadcast_height: None,
+        latest_spending_txid: Some(Txid::from_ldk_node(latest_spending_txid)),
+        confirmation_hash: Some(confirmation_hash.to_string()),
+        confirmation_height: Some(confirmation_height),
+      },
+    }
+  }
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct RoutingFees {
+  pub base_msat: u32,
+  pub proportional_millionths: u32,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct ChannelUpdateInfo {
+  pub last_update: u32,
+  pub enabled: bool,
+  pub cltv_expiry_delta: u16,
+  pub htlc_minimum_msat: u64,
+  pub htlc_maximum_msat: u64,
+  pub fees: RoutingFees,
+}
+
+impl ChannelUpdateInfo {
+  pub fn new(update: &ldk_node::lightning::routing::gossip::ChannelUpdateInfo) -> Self {
+    ChannelUpdateInfo {
+      last_update: update.last_update,
+      enabled: update.enabled,
+      cltv_expiry_delta: update.cltv_expiry_delta,
+      htlc_minimum_msat: update.htlc_minimum_msat,
+      htlc_maximum_msat: update.htlc_maximum_msat,
+      fees: RoutingFees {
+        base_msat: update.fees.base_msat,
+        proportional_millionths: update.fees.proportional_millionths,
+      },
+    }
+  }
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct ChannelInfo {
+  pub short_channel_id: u64,
+  pub node_one: String,
+  pub one_to_two: Option<ChannelUpdateInfo>,
+  pub node_two: String,
+  pub two_to_one: Option<ChannelUpdateInfo>,
+  pub capacity_sats: Option<u64>,
+}
+
+impl ChannelInfo {
+  pub fn new(
+    short_channel_id: u64,
+    channel: ldk_node::lightning::routing::gossip::ChannelInfo,
+  ) -> Self {
+    ChannelInfo {
+      short_channel_id,
+      node_one: channel.node_one.to_string(),
+      one_to_two: channel.one_to_two.as_ref().map(ChannelUpdateInfo::new),
+      node_two: channel.node_two.to_string(),
+      two_to_one: channel.two_to_one.as_ref().map(ChannelUpdateInfo::new),
+      capacity_sats: channel.capacity_sats,
+    }
+  }
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct NodeAnnouncementInfo {
+  pub last_update: u32,
+  pub alias: String,
+  pub color: String,
+  pub addresses: Vec<String>,
+  pub features: String,
+}
+
+impl NodeAnnouncementInfo {
+  pub fn new(announcement: &ldk_node::lightning::routing::gossip::NodeAnnouncementInfo) -> Self {
+    NodeAnnouncementInfo {
+      last_update: announcement.last_update,
+      alias: announcement.alias.to_string(),
+      color: format!(
+        "#{:02x}{:02x}{:02x}",
+        announcement.rgb[0], announcement.rgb[1], announcement.rgb[2]
+      ),
+      addresses: announcement
+        .addresses()
+        .iter()
+        .map(|address| address.to_string())
+        .collect(),
+      features: hex_encode(announcement.features.le_flags()),
+    }
+  }
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct NodeInfo {
+  pub node_id: String,
+  pub channels: Vec<u64>,
+  pub announcement_info: Option<NodeAnnouncementInfo>,
+}
+
+impl NodeInfo {
+  pub fn new(
+    node_id: ldk_node::lightning::routing::gossip::NodeId,
+    node: ldk_node::lightning::routing::gossip::NodeInfo,
+  ) -> Self {
+    NodeInfo {
+      node_id: node_id.to_string(),
+      channels: node.channels,
+      announcement_info: node
+        .announcement_info
+        .as_ref()
+        .map(NodeAnnouncementInfo::new),
+    }
+  }
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct BestBlock {
+  pub block_hash: String,
+  pub height: u32,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct NodeStatus {
+  pub is_running: bool,
+  pub is_listening: bool,
+  pub current_best_block: BestBlock,
+  pub latest_wallet_sync_timestamp: Option<u32>,
+  pub latest_onchain_wallet_sync_timestamp: Option<u32>,
+  pub latest_fee_rate_cache_update_timestamp: Option<u32>,
+  pub latest_rgs_snapshot_timestamp: Option<u32>,
+  pub latest_node_announcement_broadcast_timestamp: Option<u32>,
+  pub connected_peers: u32,
+  pub usable_channels: u32,
+}
+
+impl NodeStatus {
+  pub fn new(status: ldk_node::NodeStatus, connected_peers: u32, usable_channels: u32) -> Self {
+    NodeStatus {
+      is_running: status.is_running,
+      is_listening: status.is_listening,
+      current_best_block: BestBlock {
+        block_hash: status.current_best_block.block_hash.to_string(),
+        height: status.current_best_block.height,
+      },
+      latest_wallet_sync_timestamp: status.latest_wallet_sync_timestamp.map(|t| t as u32),
+      latest_onchain_wallet_sync_timestamp: status
+        .latest_onchain_wallet_sync_timestamp
+        .map(|t| t as u32),
+      latest_fee_rate_cache_update_timestamp: status
+        .latest_fee_rate_cache_update_timestamp
+        .map(|t| t as u32),
+      latest_rgs_snapshot_timestamp: status.latest_rgs_snapshot_timestamp.map(|t| t as u32),
+      latest_node_announcement_broadcast_timestamp: status
+        .latest_node_announcement_broadcast_timestamp
+        .map(|t| t as u32),
+      connected_peers,
+      usable_channels,
+    }
+  }
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OfferId {
+  pub field0: Vec<u8>,
+}
+
+impl OfferId {
+  pub fn from_ldk_node(id: ldk_node::lightning::offers::offer::OfferId) -> OfferId {
+    OfferId {
+      field0: id.0.to_vec(),
+    }
+  }
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct Offer {
+  pub offer: String,
+  pub id: OfferId,
+  pub description: String,
+  pub amount_msat: Option<u64>,
+  pub issuer: Option<String>,
+  pub absolute_expiry_secs: Option<u64>,
+}
+
+impl Offer {
+  pub fn from_ldk_node(offer: ldk_node::lightning::offers::offer::Offer) -> Self {
+    let amount_msat = match offer.amount() {
+      Some(ldk_node::lightning::offers::offer::Amount::Bitcoin { amount_msats }) => {
+        Some(*amount_msats)
+      }
+      _ => None,
+    };
+    Offer {
+      offer: offer.to_string(),
+      id: OfferId::from_ldk_node(offer.id()),
+      description: offer.description().to_string(),
+      amount_msat,
+      issuer: offer.issuer().map(|issuer| issuer.to_string()),
+      absolute_expiry_secs: offer.absolute_expiry().map(|expiry| expiry.as_secs()),
+    }
+  }
+
+  pub fn from_nodejs(offer: &str) -> Result<ldk_node::lightning::offers::offer::Offer, Error> {
+    match ldk_node::lightning::offers::offer::Offer::from_str(offer) {
+      Ok(offer) => Ok(offer),
+      Err(e) => Err(node_error(format!("Invalid offer: {:?}", e))),
+    }
+  }
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct Refund {
+  pub refund: String,
+  pub description: String,
+  pub amount_msat: u64,
+  pub payer_note: Option<String>,
+  pub absolute_expiry_secs: Option<u64>,
+}
+
+impl Refund {
+  pub fn from_ldk_node(refund: ldk_node::lightning::offers::refund::Refund) -> Self {
+    Refund {
+      refund: refund.to_string(),
+      description: refund.description().to_string(),
+      amount_msat: refund.amount_msats(),
+      payer_note: refund.payer_note().map(|note| note.to_string()),
+      absolute_expiry_secs: refund.absolute_expiry().map(|expiry| expiry.as_secs()),
+    }
+  }
+
+  pub fn from_nodejs(refund: &str) -> Result<ldk_node::lightning::offers::refund::Refund, Error> {
+    match ldk_node::lightning::offers::refund::Refund::from_str(refund) {
+      Ok(refund) => Ok(refund),
+      Err(e) => Err(node_error(format!("Invalid refund: {:?}", e))),
+    }
+  }
+}